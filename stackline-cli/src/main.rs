@@ -77,6 +77,85 @@ fn main() {
                 }
             }
 
+            Some("resize") => {
+                if let (Some(name), Some(x), Some(y), Some(width), Some(height)) = (
+                    tokens.next(),
+                    tokens.next(),
+                    tokens.next(),
+                    tokens.next(),
+                    tokens.next(),
+                ) {
+                    if let (Ok(x), Ok(y), Ok(width), Ok(height)) =
+                        (x.parse(), y.parse(), width.parse(), height.parse())
+                    {
+                        resize(&mut world, name, x, y, width, height);
+                    }
+                } else {
+                    eprintln!("Expected five arguments");
+                }
+            }
+
+            Some("wrap") => {
+                if let (Some(name), Some(horizontal), Some(vertical)) =
+                    (tokens.next(), tokens.next(), tokens.next())
+                {
+                    if let (Ok(horizontal), Ok(vertical)) = (horizontal.parse(), vertical.parse()) {
+                        match world.get_pane_mut(name) {
+                            Some(pane) => pane.set_wrap(horizontal, vertical),
+                            None => eprintln!("No pane named {}!", name),
+                        }
+                    } else {
+                        eprintln!("Syntax error: expected `true` or `false`");
+                    }
+                } else {
+                    eprintln!("Expected three arguments");
+                }
+            }
+            Some("connect") => {
+                if let (Some(name), Some(connected)) = (tokens.next(), tokens.next()) {
+                    if let Ok(connected) = connected.parse() {
+                        match world.get_pane_mut(name) {
+                            Some(pane) => pane.set_connected(connected),
+                            None => eprintln!("No pane named {}!", name),
+                        }
+                    } else {
+                        eprintln!("Syntax error: expected `true` or `false`");
+                    }
+                } else {
+                    eprintln!("Expected two arguments");
+                }
+            }
+            Some("raise") => {
+                if let Some(name) = tokens.next() {
+                    if world.raise_pane(name).is_none() {
+                        eprintln!("No pane named {}!", name);
+                    }
+                } else {
+                    eprintln!("Expected one argument");
+                }
+            }
+            Some("lower") => {
+                if let Some(name) = tokens.next() {
+                    if world.lower_pane(name).is_none() {
+                        eprintln!("No pane named {}!", name);
+                    }
+                } else {
+                    eprintln!("Expected one argument");
+                }
+            }
+
+            Some("seed") => {
+                if let Some(seed) = tokens.next() {
+                    if let Ok(seed) = seed.parse() {
+                        world.set_seed(seed);
+                    } else {
+                        eprintln!("Syntax error: expected an integer");
+                    }
+                } else {
+                    println!("{}", world.seed());
+                }
+            }
+
             Some("get") => {
                 if let (Some(x), Some(y)) = (tokens.next(), tokens.next()) {
                     if let (Ok(x), Ok(y)) = (x.parse(), y.parse()) {
@@ -250,16 +329,22 @@ fn main() {
                 println!("- `state <x> <y> <state>`: sets the state at (x, y) to `state`");
 
                 println!("- `signal <x> <y>`: adds an empty signal to the tile at (x, y)");
-                println!("- `push <x> <y> <value>`: pushes `value` to the signal at (x, y)");
+                println!("- `push <x> <y> <value>`: pushes `value` (a JSON number, string, boolean, null or array) to the signal at (x, y)");
                 println!("- `pop <x> <y>`: pops a value from the signal at (x, y)");
                 println!("- `clear <x> <y>`: clears the signal of the tile at (x, y)");
                 println!("- `dir <x> <y> <dir>`: sets the direction of the signal at (x, y)");
 
                 println!("- `pane <x> <y> <width> <height>`: creates a new pane at the given position/dimension");
-                println!("- `panes`: lists panes");
+                println!("- `resize <name> <x> <y> <width> <height>`: moves the borders of a pane, keeping its tiles in place");
+                println!("- `wrap <name> <horizontal> <vertical>`: sets whether a pane wraps around horizontally and vertically");
+                println!("- `connect <name> <connected>`: sets whether signals may leave a pane through its edges");
+                println!("- `raise <name>`: puts a pane above every other pane");
+                println!("- `lower <name>`: puts a pane below every other pane");
+                println!("- `panes`: lists panes, from the bottom-most to the top-most one");
 
                 println!("- `run <steps>`: runs a number of steps");
                 println!("- `step`: runs a single step");
+                println!("- `seed [seed]`: prints the seed of the random tiles, or sets it and restarts their sequence");
                 println!("- `load [file]`: saves the current state to `file` (defaults to the path in the parameters)");
                 println!("- `save [file]`: saves the current state to `file` (defaults to the path in the parameters)");
             }
@@ -442,17 +527,9 @@ fn push(world: &mut World, x: i32, y: i32, value: String) {
         }
     };
 
-    let value: Value = match value {
-        JValue::Number(num) => {
-            if let Some(f) = num.as_f64() {
-                Value::Number(f)
-            } else {
-                eprintln!("Unsupported value: {:?}", num);
-                return;
-            }
-        }
-        JValue::String(s) => Value::String(s),
-        x => {
+    let value: Value = match json_to_value(value) {
+        Ok(value) => value,
+        Err(x) => {
             eprintln!("Unsupported value: {:?}", x);
             return;
         }
@@ -474,6 +551,33 @@ fn push(world: &mut World, x: i32, y: i32, value: String) {
     }
 }
 
+/// Converts a JSON value into a [`Value`]: integers become [`Value::Int`], other numbers [`Value::Number`],
+/// `null` becomes [`Value::Nil`] and arrays become [`Value::List`]. Objects are not supported.
+fn json_to_value(value: serde_json::Value) -> Result<Value, serde_json::Value> {
+    use serde_json::Value as JValue;
+
+    match value {
+        JValue::Number(num) => {
+            if let Some(i) = num.as_i64() {
+                Ok(Value::Int(i))
+            } else if let Some(f) = num.as_f64() {
+                Ok(Value::Number(f))
+            } else {
+                Err(JValue::Number(num))
+            }
+        }
+        JValue::String(s) => Ok(Value::String(s)),
+        JValue::Bool(b) => Ok(Value::Bool(b)),
+        JValue::Null => Ok(Value::Nil),
+        JValue::Array(values) => values
+            .into_iter()
+            .map(json_to_value)
+            .collect::<Result<Vec<_>, _>>()
+            .map(Value::List),
+        x => Err(x),
+    }
+}
+
 fn pop(world: &mut World, x: i32, y: i32) {
     match world.get_mut((x, y)) {
         Some(tile) => match tile.take_signal() {
@@ -611,15 +715,53 @@ fn pane(world: &mut World, name: &str, x: i32, y: i32, width: usize, height: usi
     world.set_pane(name.to_string(), pane);
 }
 
+fn resize(world: &mut World, name: &str, x: i32, y: i32, width: usize, height: usize) {
+    let pane = match world.get_pane_mut(name) {
+        Some(pane) => pane,
+        None => {
+            eprintln!("No pane named {}!", name);
+            return;
+        }
+    };
+
+    let (px, py) = pane.position();
+    let origin = match (x.checked_sub(px), y.checked_sub(py)) {
+        (Some(ox), Some(oy)) => (ox, oy),
+        _ => {
+            eprintln!("Invalid pane position!");
+            return;
+        }
+    };
+    if pane.crop(origin, width, height).is_none() {
+        eprintln!("Invalid pane dimensions!");
+    }
+}
+
 fn panes(world: &World) {
-    for (name, pane) in world.panes() {
+    for name in world.pane_order() {
+        let pane = match world.get_pane(name) {
+            Some(pane) => pane,
+            None => continue,
+        };
+
+        let wrap = match pane.wrap() {
+            (true, true) => ", wraps around",
+            (true, false) => ", wraps around horizontally",
+            (false, true) => ", wraps around vertically",
+            (false, false) => "",
+        };
+
+        let connected = if pane.connected() { ", connected" } else { "" };
+
         println!(
-            "- {}: {}x{}, at {}:{}",
+            "- {}: {}x{}, at {}:{}{}{}",
             name,
             pane.width(),
             pane.height(),
             pane.position().0,
-            pane.position().1
+            pane.position().1,
+            wrap,
+            connected
         );
     }
 }
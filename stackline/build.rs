@@ -2,13 +2,14 @@ use std::env;
 use std::fmt::Write;
 use std::fs;
 use std::path::{Path, PathBuf};
-use syn::{Item, ItemImpl, Type};
+use syn::{Item, ItemImpl, Type, Visibility};
 
 // This script reads the contents of any rust file in the `tiles/` directory,
 // and gathers any type that implements `Tile`. These types are then put into
 // the `AnyTile` enum and written to `$OUT_DIR/anytile.rs`.
 //
 // Any file with a type implementing `Tile` in tiles/ will be imported privately and its type will be re-exported.
+// The other public structs and enums of that file, like the types used to configure its tiles, are re-exported as well.
 //
 // Known limitations:
 // - only impls in the format "impl Tile for X" are accepted (X must not contain any "::")
@@ -32,6 +33,7 @@ fn main() {
             let contents = fs::read_to_string(src_path.clone())
                 .unwrap_or_else(|err| panic!("Couldn't read {:?}: {}", src_path, err));
             let mut local_names: Vec<String> = Vec::new();
+            let mut local_exports: Vec<String> = Vec::new();
 
             // TODO: don't throw an error when a parsing error occured;
             // Instead, include the file so that rustc can give a helpful error
@@ -39,14 +41,18 @@ fn main() {
                 .unwrap_or_else(|err| panic!("Unable to parse file {:?}: {}", src_path, err));
 
             for item in syntax.items.iter() {
-                #[allow(clippy::single_match)]
-                // I'd like to keep the match for future-proofing, for instance if I need to match for a macro call
                 match item {
                     Item::Impl(item) => {
                         if let Some(name) = parse_impl_tile(item) {
                             local_names.push(name);
                         }
                     }
+                    Item::Struct(item) if matches!(item.vis, Visibility::Public(_)) => {
+                        local_exports.push(item.ident.to_string());
+                    }
+                    Item::Enum(item) if matches!(item.vis, Visibility::Public(_)) => {
+                        local_exports.push(item.ident.to_string());
+                    }
                     _ => {}
                 }
             }
@@ -58,6 +64,11 @@ fn main() {
                 for name in local_names.iter() {
                     names.push(name.clone());
                 }
+                for name in local_exports {
+                    if !local_names.contains(&name) {
+                        local_names.push(name);
+                    }
+                }
                 files.push((canonical, local_names));
             }
         }
@@ -1,4 +1,5 @@
 use super::*;
+use std::collections::{BTreeMap, HashSet};
 use veccell::{VecRef, VecRefMut};
 
 /** Provides an interface between a [`Tile`] and its parent [`Pane`] during [`Tile::update`].
@@ -59,6 +60,59 @@ pub struct UpdateContext<'a> {
     state: State,
     signal: Option<Signal>,
     commit: &'a mut UpdateCommit,
+    env: UpdateEnv<'a>,
+    random_draws: u64,
+    pane_names: u64,
+}
+
+/// State of the [`World`] that tiles may access through [`UpdateContext`] while their pane is being updated.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct UpdateEnv<'a> {
+    pub(crate) globals: &'a BTreeMap<String, Value>,
+    /// Seed of the pseudo-random numbers returned by [`UpdateContext::random`]
+    pub(crate) seed: u64,
+    /// Number of update cycles that the world went through
+    pub(crate) step: u64,
+    /// Number of update cycles that the world went through since it was created, used by [`UpdateContext::unique_pane_name`]
+    pub(crate) age: u64,
+    /// Hash of the positions in the world of the tiles embedding the pane, like [`Chip`](crate::tile::Chip)s;
+    /// zero for the panes of the world
+    pub(crate) scope: u64,
+    /// Destinations of the signals leaving the pane through its edges, alongside their direction,
+    /// where a tile accepted such signals at the beginning of the update cycle
+    pub(crate) edges: Option<&'a HashSet<((i32, i32), Direction)>>,
+}
+
+static NO_GLOBALS: BTreeMap<String, Value> = BTreeMap::new();
+
+impl UpdateEnv<'static> {
+    /// The environment of a [`Pane`] updated outside of any [`World`]
+    pub(crate) fn empty() -> Self {
+        Self {
+            globals: &NO_GLOBALS,
+            seed: 0,
+            step: 0,
+            age: 0,
+            scope: 0,
+            edges: None,
+        }
+    }
+}
+
+/// Finalizer of SplitMix64, used to turn a counter into a pseudo-random number
+#[inline]
+fn mix64(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+/// Hashes `values` together with [`mix64`], starting from `seed`
+#[inline]
+fn mix_all<const N: usize>(seed: u64, values: [u64; N]) -> u64 {
+    values
+        .into_iter()
+        .fold(mix64(seed), |hash, n| mix64(hash ^ n))
 }
 
 // SAFETY: self.pane.tiles[self.position].cell may not be accessed by any method of UpdateContext
@@ -69,6 +123,7 @@ impl<'a> UpdateContext<'a> {
         pane: &'b Pane,
         position: (usize, usize),
         commit: &'a mut UpdateCommit,
+        env: UpdateEnv<'a>,
     ) -> Option<(UpdateContext<'a>, VecRefMut<'b, FullTile>)>
     where
         'b: 'a, // 'b ⊇ 'a
@@ -86,6 +141,9 @@ impl<'a> UpdateContext<'a> {
             signal: tile.take_signal(),
             pane,
             commit,
+            env,
+            random_draws: 0,
+            pane_names: 0,
         };
 
         Some((res, tile))
@@ -209,6 +267,7 @@ impl<'a> UpdateContext<'a> {
     }
 
     /// Returns `Some((position.x + Δx, position.y + Δy))` iff `(x + Δx, y + Δy)` is inside the current pane.
+    /// If the current pane [wraps around](Pane::set_wrap), then the coordinates wrap around accordingly.
     #[inline]
     pub fn offset(&self, offset: (i8, i8)) -> Option<(usize, usize)> {
         self.pane.offset(self.position, offset)
@@ -325,12 +384,214 @@ impl<'a> UpdateContext<'a> {
         }
     }
 
+    /// Returns `true` iff moving from the current tile towards `direction` leaves the current [`Pane`],
+    /// and the pane is [connected](Pane::set_connected) to its neighbors.
+    #[inline]
+    pub fn crosses_edge(&self, direction: Direction) -> bool {
+        self.pane.connected() && self.offset(direction.into_offset()).is_none()
+    }
+
+    /// Returns `true` iff [`crosses_edge(direction)`](UpdateContext::crosses_edge) is `true`
+    /// and the tile found through that edge, in the [`World`], accepts a signal travelling towards `direction`.
+    ///
+    /// This is the counterpart of [`accepts_direction`](UpdateContext::accepts_direction) for edges:
+    /// a signal sent with [`send_edge`](UpdateContext::send_edge) is received iff this method returns `true`.
+    /// If the current pane isn't updated as part of a `World`, then this method always returns `false`.
+    #[inline]
+    pub fn accepts_edge(&self, direction: Direction) -> bool {
+        self.crosses_edge(direction)
+            && self.env.edges.is_some_and(|edges| {
+                edges.contains(&(
+                    self.pane.edge_destination(self.position, direction),
+                    direction,
+                ))
+            })
+    }
+
+    /// Sends a signal out of the current [`Pane`], through the edge found towards `direction`.
+    /// Sets the signal direction to `direction`.
+    ///
+    /// The signal will be received by the tile at the matching coordinates of the [`World`] if that tile
+    /// [accepted](Tile::accepts_signal) it at the beginning of the update cycle, as told by [`accepts_edge`](UpdateContext::accepts_edge);
+    /// otherwise, the signal will be lost.
+    /// Returns an error if [`crosses_edge(direction)`](UpdateContext::crosses_edge) is `false`.
+    ///
+    /// # Note
+    ///
+    /// The actions of this function will only be executed *after* all the panes of the [`World`] were [`updated`](World::step).
+    pub fn send_edge(&mut self, direction: Direction, signal: Signal) -> Result<(), SendError> {
+        if !self.crosses_edge(direction) {
+            return Err(SendError(signal));
+        }
+
+        let position = self.pane.edge_destination(self.position, direction);
+        self.commit.send_edge(position, signal.moved(direction));
+
+        Ok(())
+    }
+
+    /// Sends a signal to the tile next to the current one towards `direction`, if that tile will accept our signal.
+    /// If `direction` leads out of the current pane, then the signal is sent through its edge with [`send_edge`](UpdateContext::send_edge).
+    /// Sets the signal direction to `direction`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use stackline::tile::prelude::*;
+    /// # #[derive(Clone, Debug, Serialize, Deserialize, Default)]
+    /// # pub struct MyTile;
+    /// # impl Tile for MyTile {
+    /// fn update<'b>(&'b mut self, mut ctx: UpdateContext<'b>) {
+    ///     if let Some(signal) = ctx.take_signal() {
+    ///         let _ = ctx.send_direction(Direction::Down, signal);
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub fn send_direction(
+        &mut self,
+        direction: Direction,
+        signal: Signal,
+    ) -> Result<(), SendError> {
+        match self.offset(direction.into_offset()) {
+            Some(pos) => self.send(pos, direction, signal),
+            None => self.send_edge(direction, signal),
+        }
+    }
+
     /// Sends a signal to another [`Pane`] in the world.
     /// If the tile at `coordinates = (pane, x, y)` does not exist, then the signal will be lost.
     pub fn send_outbound(&mut self, coordinates: (String, usize, usize), signal: Signal) {
         self.commit.send_outbound(coordinates, signal);
     }
 
+    /// Requests a copy of the pane named `source` to be inserted in the [`World`] at `position`, under the name `name`.
+    /// The copy includes the current tiles, states and signals of `source`.
+    ///
+    /// Nothing happens if there is no pane named `source` or if there already is a pane named `name`.
+    ///
+    /// # Note
+    ///
+    /// The actions of this function will only be executed *after* all the panes of the [`World`] were [`updated`](World::step),
+    /// but before any signal is sent to another pane.
+    pub fn instantiate_pane(&mut self, source: String, name: String, position: (i32, i32)) {
+        self.commit.pane_operation(PaneOperation::Instantiate {
+            source,
+            name,
+            position,
+        });
+    }
+
+    /// Returns a pane name made of `prefix`, followed by `#`, by the number of update cycles that the [`World`] went through since it was created,
+    /// and by a hash of the position of the current tile in the world and of the number of times that this method was called during the current update.
+    ///
+    /// As such, the name does not depend on the order in which the tiles are updated, and every call made during the lifetime of the world
+    /// gets a different name (barring 64-bit hash collisions), so that [`instantiate_pane`](UpdateContext::instantiate_pane) creates a new pane for every call.
+    /// If the current pane isn't updated as part of a `World`, then the number of update cycles is zero.
+    pub fn unique_pane_name(&mut self, prefix: &str) -> String {
+        let (x, y) = self.world_position();
+        self.pane_names += 1;
+
+        let hash = mix_all(
+            self.env.age ^ self.env.scope,
+            [x as u64, y as u64, self.pane_names],
+        );
+
+        format!("{}#{}.{:x}", prefix, self.env.age, hash)
+    }
+
+    /// Requests the pane named `name` to be removed from the [`World`], along with its signals.
+    /// Nothing happens if there is no such pane.
+    ///
+    /// # Note
+    ///
+    /// The actions of this function will only be executed *after* all the panes of the [`World`] were [`updated`](World::step),
+    /// but before any signal is sent to another pane.
+    pub fn delete_pane(&mut self, name: String) {
+        self.commit.pane_operation(PaneOperation::Delete { name });
+    }
+
+    /// Returns the value of the global variable named `key` of the [`World`], if it exists.
+    ///
+    /// The global variables are only modified after every pane of the world was updated,
+    /// so this method returns the value that the variable had at the beginning of the update cycle.
+    /// If the current pane isn't updated as part of a `World`, then there are no global variables.
+    #[inline]
+    pub fn get_global(&self, key: &str) -> Option<&'a Value> {
+        self.env.globals.get(key)
+    }
+
+    /// Sets the global variable named `key` of the [`World`] to `value`.
+    ///
+    /// # Note
+    ///
+    /// The actions of this function will only be executed *after* all the panes of the [`World`] were [`updated`](World::step).
+    /// If several tiles write to the same variable during an update cycle, then the writes are applied in the stacking order of their panes,
+    /// from the bottom-most to the top-most one, and in reading order (left to right, then top to bottom) within each pane.
+    /// The last write wins, which means that the write of the top-most pane and of the tile that comes last in reading order is kept.
+    pub fn set_global(&mut self, key: impl Into<String>, value: Value) {
+        self.commit
+            .write_global(self.position, key.into(), Some(value));
+    }
+
+    /// Removes the global variable named `key` of the [`World`].
+    /// This follows the same rules as [`set_global`](UpdateContext::set_global).
+    pub fn remove_global(&mut self, key: impl Into<String>) {
+        self.commit.write_global(self.position, key.into(), None);
+    }
+
+    /// Returns a pseudo-random number, derived from the [seed](World::seed) of the [`World`], from the number of update cycles
+    /// that it went through, from the position of the current tile in the world and from the number of times that this method was called
+    /// during the current update. The tiles of a pane embedded in a [`Chip`](crate::tile::Chip) also take the position of the chip into account.
+    ///
+    /// As such, the numbers do not depend on the order in which the tiles are updated, and running the same world twice
+    /// from the same seed yields the same numbers.
+    /// If the current pane isn't updated as part of a `World`, then the seed and the number of update cycles are both zero.
+    pub fn random(&mut self) -> u64 {
+        let (x, y) = self.world_position();
+        self.random_draws += 1;
+
+        mix_all(
+            self.env.seed ^ self.env.scope,
+            [self.env.step, x as u64, y as u64, self.random_draws],
+        )
+    }
+
+    /// Returns a pseudo-random number between `0` (included) and `bound` (excluded), using [`random`](UpdateContext::random).
+    /// Returns `0` if `bound` is zero.
+    pub fn random_range(&mut self, bound: u64) -> u64 {
+        ((self.random() as u128 * bound as u128) >> 64) as u64
+    }
+
+    /// Returns the position of the current tile in the [`World`]
+    #[inline]
+    fn world_position(&self) -> (i64, i64) {
+        (
+            self.pane.position().0 as i64 + self.position.0 as i64,
+            self.pane.position().1 as i64 + self.position.1 as i64,
+        )
+    }
+
+    /// Returns the environment with which a [`Pane`] embedded in the current tile should be updated.
+    ///
+    /// The pseudo-random numbers and the pane names drawn by the tiles of the embedded pane are derived from
+    /// the position of the current tile in the world, so that they differ from the ones drawn by the tiles of the world.
+    /// Signals leaving the embedded pane through its edges are only received if `edges` contains their destination and direction.
+    pub(crate) fn inner_env<'b>(&self, edges: &'b HashSet<((i32, i32), Direction)>) -> UpdateEnv<'b>
+    where
+        'a: 'b,
+    {
+        let (x, y) = self.world_position();
+        // The golden ratio constant of SplitMix64 keeps a chip at (0, 0) from getting the scope of the world
+        let scope = mix_all(self.env.scope ^ 0x9e3779b97f4a7c15, [x as u64, y as u64]);
+
+        UpdateEnv {
+            scope,
+            edges: Some(edges),
+            ..self.env
+        }
+    }
+
     /// Stores the current signal back in the current tile, guaranteeing that it will stay there for
     /// this update cycle. See [`take_signal`](UpdateContext::take_signal) for more information.
     ///
@@ -397,6 +658,9 @@ pub(crate) struct UpdateCommit {
     updates: Vec<(usize, usize)>,
     callbacks: Vec<UpdateCommitCallback>,
     outbound_signals: Vec<((String, usize, usize), Signal)>,
+    edge_signals: Vec<((i32, i32), Signal)>,
+    pane_operations: Vec<PaneOperation>,
+    global_writes: Vec<((usize, usize), String, Option<Value>)>,
 
     self_signal: Option<Signal>,
 }
@@ -409,6 +673,9 @@ impl UpdateCommit {
             updates: Vec::new(),
             callbacks: Vec::new(),
             outbound_signals: Vec::new(),
+            edge_signals: Vec::new(),
+            pane_operations: Vec::new(),
+            global_writes: Vec::new(),
 
             self_signal: None,
         }
@@ -434,7 +701,19 @@ impl UpdateCommit {
         self.outbound_signals.push((coordinates, signal));
     }
 
-    pub(crate) fn apply(self, pane: &mut Pane) -> PaneResult {
+    fn send_edge(&mut self, position: (i32, i32), signal: Signal) {
+        self.edge_signals.push((position, signal));
+    }
+
+    fn pane_operation(&mut self, operation: PaneOperation) {
+        self.pane_operations.push(operation);
+    }
+
+    fn write_global(&mut self, pos: (usize, usize), key: String, value: Option<Value>) {
+        self.global_writes.push((pos, key, value));
+    }
+
+    pub(crate) fn apply(mut self, pane: &mut Pane) -> PaneResult {
         for (x, y) in self.updates {
             if let Some(tile) = pane.get_mut((x, y)) {
                 tile.updated = false;
@@ -449,7 +728,10 @@ impl UpdateCommit {
 
         for (x, y, signal) in self.signals {
             let push_signal = if let Some(tile) = pane.get_mut((x, y)) {
-                tile.set_signal(signal);
+                match signal {
+                    Some(signal) => tile.receive_signal(signal),
+                    None => tile.set_signal(None),
+                };
                 tile.set_state(State::Active);
                 // For some reason std::mem::drop(tile) isn't enough here
                 true
@@ -466,8 +748,19 @@ impl UpdateCommit {
             (callback)(pane);
         }
 
+        // Sort the writes in reading order, to keep the update order-agnostic; the sort is stable,
+        // so the writes of a single tile are kept in the order in which they were made
+        self.global_writes.sort_by_key(|&((x, y), _, _)| (y, x));
+
         PaneResult {
             outbound_signals: self.outbound_signals,
+            edge_signals: self.edge_signals,
+            pane_operations: self.pane_operations,
+            global_writes: self
+                .global_writes
+                .into_iter()
+                .map(|(_, key, value)| (key, value))
+                .collect(),
         }
     }
 
@@ -2,7 +2,7 @@ use super::*;
 use serde::{Deserialize, Serialize};
 use veccell::{VecCell, VecRef, VecRefMut};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Pane {
     tiles: VecCell<FullTile>,
     width: NonZeroUsize,
@@ -10,6 +10,14 @@ pub struct Pane {
 
     position: (i32, i32),
 
+    /// Whether the pane wraps around horizontally and vertically
+    #[serde(default)]
+    wrap: (bool, bool),
+
+    /// Whether signals may leave the pane through its edges
+    #[serde(default)]
+    connected: bool,
+
     pub(crate) signals: Vec<(usize, usize)>,
 }
 
@@ -51,6 +59,8 @@ impl Pane {
             tiles,
 
             position: (0, 0),
+            wrap: (false, false),
+            connected: false,
 
             signals: Vec::new(),
         })
@@ -99,40 +109,260 @@ impl Pane {
         self.position = position;
     }
 
+    /// Returns whether the `Pane` wraps around horizontally and vertically, as a pair `(horizontal, vertical)`.
+    /// See [`set_wrap`](Pane::set_wrap) for more information.
+    #[inline]
+    pub fn wrap(&self) -> (bool, bool) {
+        self.wrap
+    }
+
+    /// Sets whether the `Pane` wraps around horizontally and vertically.
+    ///
+    /// A pane wrapping around horizontally has its left and right edges connected to each other,
+    /// and a pane wrapping around vertically has its top and bottom edges connected to each other.
+    /// A pane wrapping around in both directions is thus a torus.
+    ///
+    /// This changes the behavior of [`offset`](Pane::offset), and thus of [`UpdateContext::offset`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use stackline::prelude::*;
+    /// let mut pane = Pane::empty(4, 2).unwrap();
+    ///
+    /// assert_eq!(pane.offset((3, 0), (1, 0)), None);
+    ///
+    /// pane.set_wrap(true, false);
+    ///
+    /// assert_eq!(pane.offset((3, 0), (1, 0)), Some((0, 0)));
+    /// assert_eq!(pane.offset((0, 0), (0, -1)), None);
+    /// ```
+    #[inline]
+    pub fn set_wrap(&mut self, horizontal: bool, vertical: bool) {
+        self.wrap = (horizontal, vertical);
+    }
+
+    /// Returns whether the `Pane` is connected to its neighbors.
+    /// See [`set_connected`](Pane::set_connected) for more information.
+    #[inline]
+    pub fn connected(&self) -> bool {
+        self.connected
+    }
+
+    /// Sets whether the `Pane` is connected to its neighbors.
+    ///
+    /// Signals leaving a connected pane through one of its edges (see [`UpdateContext::send_edge`])
+    /// will enter the pane found at the matching coordinates of the [`World`], if any,
+    /// instead of being lost. Edges along which the pane [wraps around](Pane::set_wrap) are never crossed.
+    ///
+    /// Only the pane that the signal leaves needs to be connected.
+    #[inline]
+    pub fn set_connected(&mut self, connected: bool) {
+        self.connected = connected;
+    }
+
+    /// Returns the coordinates in the [`World`] reached by a signal leaving the tile at `position` towards `direction`,
+    /// assuming that it crosses an edge of the pane.
+    #[inline]
+    pub(crate) fn edge_destination(
+        &self,
+        position: (usize, usize),
+        direction: Direction,
+    ) -> (i32, i32) {
+        let (dx, dy) = direction.into_offset();
+
+        (
+            self.position.0 + position.0 as i32 + dx as i32,
+            self.position.1 + position.1 as i32 + dy as i32,
+        )
+    }
+
+    /// Returns the coordinates in the [`World`] that signals may reach by leaving the pane through one of its edges,
+    /// alongside the direction in which they leave. Returns nothing if the pane isn't [connected](Pane::connected).
+    pub(crate) fn edge_destinations(&self) -> Vec<((i32, i32), Direction)> {
+        if !self.connected {
+            return Vec::new();
+        }
+
+        let (width, height) = (self.width.get(), self.height.get());
+        let mut res = Vec::new();
+
+        for y in 0..height {
+            // Only the first and last tiles of the inner rows lie on an edge
+            let x_step = if y == 0 || y == height - 1 {
+                1
+            } else {
+                (width - 1).max(1)
+            };
+
+            for x in (0..width).step_by(x_step) {
+                for &direction in Orientation::Any.into_directions() {
+                    if self.offset((x, y), direction.into_offset()).is_none() {
+                        res.push((self.edge_destination((x, y), direction), direction));
+                    }
+                }
+            }
+        }
+
+        res
+    }
+
+    /// Resizes the `Pane` to `width × height`, keeping its top-left corner where it is.
+    /// Tiles and signals that fall outside of the new bounds are dropped, and new tiles are left empty.
+    ///
+    /// If `width == 0` or `height == 0`, returns `None` and leaves the `Pane` untouched.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use stackline::prelude::*;
+    /// use stackline::tile::Wire;
+    ///
+    /// let mut pane = Pane::empty(2, 2).unwrap();
+    /// pane.set_tile((1, 1), Wire::new(Orientation::Any));
+    ///
+    /// pane.resize(4, 3).unwrap();
+    ///
+    /// assert_eq!(pane.width().get(), 4);
+    /// assert_eq!(pane.height().get(), 3);
+    /// assert!(pane.get_as::<Wire>((1, 1)).is_some());
+    /// ```
+    pub fn resize(&mut self, width: usize, height: usize) -> Option<()> {
+        self.crop((0, 0), width, height)
+    }
+
+    /// Crops the `Pane` to the `width × height` rectangle whose top-left corner is at `origin`,
+    /// with `origin` being relative to the current top-left corner of the `Pane`.
+    ///
+    /// `origin` may be negative and the rectangle may extend past the current bounds,
+    /// in which case the `Pane` grows in that direction and the new tiles are left empty.
+    /// The [`position`](Pane::position) of the `Pane` is shifted by `origin`, so that the remaining tiles
+    /// keep their coordinates in the [`World`].
+    ///
+    /// Tiles, signals and pending signal updates are moved to their new coordinates;
+    /// those that fall outside of the rectangle are dropped.
+    ///
+    /// If `width == 0` or `height == 0`, returns `None` and leaves the `Pane` untouched.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use stackline::prelude::*;
+    /// use stackline::tile::Wire;
+    ///
+    /// let mut pane = Pane::empty(2, 2).unwrap();
+    /// pane.set_tile((0, 0), Wire::new(Orientation::Any));
+    ///
+    /// // Grow the pane by one tile to the left and to the top
+    /// pane.crop((-1, -1), 3, 3).unwrap();
+    ///
+    /// assert_eq!(pane.position(), (-1, -1));
+    /// assert!(pane.get((0, 0)).unwrap().get().is_none());
+    /// assert!(pane.get_as::<Wire>((1, 1)).is_some());
+    /// ```
+    pub fn crop(&mut self, origin: (i32, i32), width: usize, height: usize) -> Option<()> {
+        let length = width.checked_mul(height)?;
+        let new_width: NonZeroUsize = width.try_into().ok()?;
+        let new_height: NonZeroUsize = height.try_into().ok()?;
+        let position = (
+            self.position.0.checked_add(origin.0)?,
+            self.position.1.checked_add(origin.1)?,
+        );
+
+        let old_width = self.width.get() as isize;
+        let old_height = self.height.get() as isize;
+        let (ox, oy) = (origin.0 as isize, origin.1 as isize);
+
+        let mut old_tiles: Vec<FullTile> = std::mem::replace(&mut self.tiles, VecCell::new())
+            .into_iter()
+            .collect();
+        let mut tiles = VecCell::with_capacity(length);
+
+        for y in 0..height {
+            for x in 0..width {
+                let old_x = x as isize + ox;
+                let old_y = y as isize + oy;
+
+                if old_x < 0 || old_x >= old_width || old_y < 0 || old_y >= old_height {
+                    tiles.push(FullTile::default());
+                    continue;
+                }
+
+                let mut tile = std::mem::take(&mut old_tiles[(old_y * old_width + old_x) as usize]);
+                if let Some(mut signal) = tile.take_signal() {
+                    signal.set_position((x, y));
+                    tile.set_signal(Some(signal));
+                }
+                tiles.push(tile);
+            }
+        }
+
+        self.signals = std::mem::take(&mut self.signals)
+            .into_iter()
+            .filter_map(|(x, y)| {
+                let new_x = x as isize - ox;
+                let new_y = y as isize - oy;
+
+                if new_x >= 0 && new_x < width as isize && new_y >= 0 && new_y < height as isize {
+                    Some((new_x as usize, new_y as usize))
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        self.tiles = tiles;
+        self.width = new_width;
+        self.height = new_height;
+        self.position = position;
+
+        Some(())
+    }
+
     /// Given a `position = (x, y)` and an `offset = (Δx, Δy)`,
     /// returns `Some((x + Δx, y + Δy))` if `(x + Δx, y + Δy)` is inside the `Pane`.
     ///
     /// If `(x + Δx, y + Δy)` fall outside of the bounds of `Pane`, returns `None`.
+    /// If the `Pane` [wraps around](Pane::set_wrap) along an axis, then the coordinate along that axis
+    /// wraps around instead, and will always fall inside of the bounds of the `Pane`.
     ///
     /// # Example
     ///
     /// ```
     /// # use stackline::prelude::*;
     /// #
-    /// let pane = Pane::empty(4, 2).unwrap();
+    /// let mut pane = Pane::empty(4, 2).unwrap();
     ///
     /// assert_eq!(pane.offset((1, 0), (2, 1)), Some((3, 1))); // (1 + 2, 0 + 1) = (3, 1), inside
     ///
     /// assert_eq!(pane.offset((1, 0), (-2, 0)), None); // (1 - 2, 0 + 0) = (-1, 0), outside
     ///
     /// assert_eq!(pane.offset((1, 0), (3, 0)), None); // (1 + 3, 0 + 0) = (4, 0), outside
+    ///
+    /// pane.set_wrap(true, true);
+    ///
+    /// assert_eq!(pane.offset((1, 0), (-2, 0)), Some((3, 0))); // (1 - 2, 0 + 0) = (-1, 0), wraps around to (3, 0)
+    ///
+    /// assert_eq!(pane.offset((1, 0), (3, -1)), Some((0, 1))); // (1 + 3, 0 - 1) = (4, -1), wraps around to (0, 1)
     /// ```
     #[inline]
     pub fn offset(&self, position: (usize, usize), offset: (i8, i8)) -> Option<(usize, usize)> {
-        if offset.0 < 0 && (-offset.0) as usize > position.0
-            || offset.1 < 0 && (-offset.1) as usize > position.1
-        {
-            return None;
-        }
+        Some((
+            Self::offset_axis(position.0, offset.0, self.width.get(), self.wrap.0)?,
+            Self::offset_axis(position.1, offset.1, self.height.get(), self.wrap.1)?,
+        ))
+    }
 
+    /// Adds `offset` to `position` along a single axis of length `length`
+    #[inline]
+    fn offset_axis(position: usize, offset: i8, length: usize, wrap: bool) -> Option<usize> {
         // TODO: check that position and position + offset are valid isize values
-        let new_pos = (
-            (position.0 as isize + offset.0 as isize) as usize,
-            (position.1 as isize + offset.1 as isize) as usize,
-        );
+        let new_pos = position as isize + offset as isize;
 
-        if new_pos.0 < self.width.get() && new_pos.1 < self.height.get() {
-            Some(new_pos)
+        if wrap {
+            Some(new_pos.rem_euclid(length as isize) as usize)
+        } else if new_pos >= 0 && (new_pos as usize) < length {
+            Some(new_pos as usize)
         } else {
             None
         }
@@ -349,7 +579,7 @@ impl Pane {
     /// Returns `Some(())` if the tile exists and the tile can have a signal.
     ///
     /// This function does not check the tile's [`accepts_signal`](Tile::accepts_signal) method.
-    /// It will also overwrite any signal already present.
+    /// It will also overwrite any signal already present, unless the tile [absorbs](Tile::absorb_signal) the signal.
     ///
     /// # Example
     ///
@@ -374,7 +604,7 @@ impl Pane {
     pub fn set_signal(&mut self, position: (usize, usize), mut signal: Signal) -> Option<()> {
         signal.set_position(position);
         if let Some(tile) = self.get_mut(position) {
-            tile.set_signal(Some(signal))?;
+            tile.receive_signal(signal)?;
             tile.set_state(State::Active);
             self.signals.push(position);
             Some(())
@@ -402,9 +632,14 @@ impl Pane {
     }
 
     #[inline]
-    fn update(&mut self, position: (usize, usize), commit: &mut UpdateCommit) -> Option<()> {
+    fn update(
+        &mut self,
+        position: (usize, usize),
+        commit: &mut UpdateCommit,
+        env: UpdateEnv<'_>,
+    ) -> Option<()> {
         // NOTE: Tiles will only be updated once as per UpdateContext::new
-        let (ctx, mut tile) = UpdateContext::new(self, position, commit)?;
+        let (ctx, mut tile) = UpdateContext::new(self, position, commit, env)?;
 
         (*tile).get_mut()?.update(ctx);
 
@@ -463,16 +698,21 @@ impl Pane {
     /// assert!(pane.get((1, 1)).unwrap().signal().is_some());
     /// ```
     pub fn step(&mut self) -> PaneResult {
+        self.step_with(UpdateEnv::empty())
+    }
+
+    /// Performs an update cycle, giving the tiles access to `env`. See [`step`](Pane::step) for more information.
+    pub(crate) fn step_with(&mut self, env: UpdateEnv<'_>) -> PaneResult {
         let mut commit = UpdateCommit::new();
 
         for position in std::mem::take(&mut self.signals) {
-            let _ = self.update(position, &mut commit);
+            let _ = self.update(position, &mut commit, env);
         }
 
         for y in 0..self.height.get() {
             for x in 0..self.width.get() {
                 if self.get_state((x, y)).unwrap() != State::Idle {
-                    let _ = self.update((x, y), &mut commit);
+                    let _ = self.update((x, y), &mut commit, env);
                 }
             }
         }
@@ -480,6 +720,37 @@ impl Pane {
         commit.apply(self)
     }
 
+    /// Returns `true` iff no tile of the pane holds a signal or is in a state other than [`Idle`](State::Idle),
+    /// in which case [`step`](Pane::step) would do nothing.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use stackline::prelude::*;
+    /// use stackline::tile::Wire;
+    ///
+    /// let mut pane = Pane::empty(1, 1).unwrap();
+    /// pane.set_tile((0, 0), Wire::new(Orientation::Any));
+    ///
+    /// assert!(pane.is_idle());
+    ///
+    /// pane.set_signal((0, 0), stackline::signal!((0, 0)));
+    /// assert!(!pane.is_idle());
+    ///
+    /// // The wire goes through the Dormant state before being Idle again
+    /// pane.step();
+    /// assert!(!pane.is_idle());
+    /// pane.step();
+    /// assert!(pane.is_idle());
+    /// ```
+    pub fn is_idle(&self) -> bool {
+        self.signals.is_empty()
+            && self
+                .tiles
+                .iter()
+                .all(|tile| tile.state() == State::Idle && tile.signal().is_none())
+    }
+
     /// Returns an iterator over the tiles and their coordinates
     #[inline]
     pub fn tiles(&self) -> impl Iterator<Item = (usize, usize, VecRef<'_, FullTile>)> + '_ {
@@ -505,6 +776,16 @@ impl Pane {
 pub struct PaneResult {
     /// Signals to be sent to other panes.
     pub outbound_signals: Vec<((String, usize, usize), Signal)>,
+
+    /// Signals that left the pane through one of its edges, along with their destination in world coordinates.
+    pub edge_signals: Vec<((i32, i32), Signal)>,
+
+    /// Operations to be made on the panes of the world.
+    pub pane_operations: Vec<PaneOperation>,
+
+    /// Writes to the global variables of the world, in the order in which they should be applied.
+    /// A value of `None` removes the variable.
+    pub global_writes: Vec<(String, Option<Value>)>,
 }
 
 #[cfg(test)]
@@ -614,4 +895,120 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn test_pane_wrap() {
+        use crate::tile::Diode;
+        use Direction::*;
+
+        let horizontal = test_tile_setup!(
+            3,
+            1,
+            [Diode::new(Right), Diode::new(Right), Diode::new(Right)]
+        );
+        let vertical = test_tile_setup!(1, 3, [Diode::new(Up), Diode::new(Up), Diode::new(Up)]);
+
+        for (mut pane, direction, positions) in [
+            (horizontal, Right, [(2, 0), (0, 0), (1, 0)]),
+            (vertical, Up, [(0, 0), (0, 2), (0, 1)]),
+        ] {
+            // Without wrapping, the signal is lost
+            test_set_signal!(pane, positions[0], direction);
+            pane.step();
+            for (_, _, tile) in pane.tiles() {
+                assert!(tile.signal().is_none());
+            }
+
+            // Cool down
+            pane.step();
+            pane.step();
+
+            pane.set_wrap(direction == Right, direction == Up);
+            test_set_signal!(pane, positions[0], direction);
+
+            for &pos in positions.iter().cycle().skip(1).take(6) {
+                pane.step();
+                assert_signal!(pane, pos);
+            }
+
+            // Wrapping is preserved through serialization
+            let wrap = pane.wrap();
+            let pane: Pane = serde_json::from_str(&serde_json::to_string(&pane).unwrap()).unwrap();
+            assert_eq!(pane.wrap(), wrap);
+        }
+    }
+
+    #[test]
+    fn test_pane_resize() {
+        use crate::tile::Wire;
+        use Orientation::*;
+
+        let mut pane = test_tile_setup!(
+            2,
+            2,
+            [
+                Wire::new(Horizontal),
+                Wire::new(Vertical),
+                Wire::new(Any),
+                ()
+            ]
+        );
+        test_set_signal!(pane, (0, 1), Direction::Right);
+
+        pane.resize(3, 1).unwrap();
+
+        assert_eq!(pane.width().get(), 3);
+        assert_eq!(pane.height().get(), 1);
+        assert_eq!(pane.position(), (0, 0));
+        assert_eq!(*pane.get_as::<Wire>((0, 0)).unwrap(), Wire::new(Horizontal));
+        assert_eq!(*pane.get_as::<Wire>((1, 0)).unwrap(), Wire::new(Vertical));
+        assert!(pane.get((2, 0)).unwrap().get().is_none());
+
+        // The signal at (0, 1) was cropped out
+        assert!(pane.signals.is_empty());
+        for (_, _, tile) in pane.tiles() {
+            assert!(tile.signal().is_none());
+        }
+
+        assert!(pane.resize(0, 1).is_none());
+        assert_eq!(pane.width().get(), 3);
+    }
+
+    #[test]
+    fn test_pane_crop_negative() {
+        use crate::tile::Wire;
+        use Orientation::*;
+
+        let mut pane = test_tile_setup!(
+            2,
+            2,
+            [
+                Wire::new(Any),
+                Wire::new(Horizontal),
+                Wire::new(Vertical),
+                ()
+            ]
+        );
+        pane.set_position((5, 5));
+        test_set_signal!(pane, (0, 1), Direction::Up);
+
+        pane.crop((-2, -1), 3, 4).unwrap();
+
+        assert_eq!(pane.position(), (3, 4));
+        assert_eq!(*pane.get_as::<Wire>((2, 1)).unwrap(), Wire::new(Any));
+        assert_eq!(*pane.get_as::<Wire>((2, 2)).unwrap(), Wire::new(Vertical));
+        assert!(pane.get((0, 0)).unwrap().get().is_none());
+
+        // The signal was moved along with its tile
+        assert_eq!(pane.signals, vec![(2, 2)]);
+        assert_signal!(pane, (2, 2));
+        assert_eq!(
+            pane.get((2, 2)).unwrap().signal().unwrap().position(),
+            (2, 2)
+        );
+
+        pane.step();
+        assert_no_signal!(pane, (2, 2));
+        assert_signal!(pane, (2, 1));
+    }
 }
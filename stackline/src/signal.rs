@@ -1,24 +1,129 @@
 use super::*;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+/// A value that can be stored in the [`stack`](Signal::stack) or in the [`heap`](Signal::heap) of a [`Signal`].
+///
+/// ## Example
+///
+/// ```
+/// # use stackline::prelude::*;
+/// assert_eq!(Value::from(3), Value::Int(3));
+/// assert_eq!(Value::from(3.5), Value::Number(3.5));
+/// assert_eq!(Value::from(true), Value::Bool(true));
+/// assert_eq!(Value::from(()), Value::Nil);
+/// assert_eq!(
+///     Value::from(vec![1, 2]),
+///     Value::List(vec![Value::Int(1), Value::Int(2)])
+/// );
+///
+/// // Integers keep their precision above 2^53
+/// let big = Value::from(i64::MAX);
+/// assert_eq!(big.as_int(), Some(i64::MAX));
+/// assert_eq!(big.as_number(), Some(i64::MAX as f64));
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default)]
 pub enum Value {
     Number(f64),
     String(String),
+    Int(i64),
+    Bool(bool),
+    #[default]
+    Nil,
+    List(Vec<Value>),
 }
 
 impl Value {
+    /// Returns the value as a floating-point number, if it is a [`Number`](Value::Number) or an [`Int`](Value::Int).
     #[inline]
     pub fn as_number(&self) -> Option<f64> {
         match self {
             Value::Number(x) => Some(*x),
+            Value::Int(x) => Some(*x as f64),
             _ => None,
         }
     }
 
+    /// Returns the value as an integer, if it is an [`Int`](Value::Int) or a [`Number`](Value::Number).
+    /// Numbers are truncated towards zero.
     #[inline]
     pub fn as_int(&self) -> Option<i64> {
-        self.as_number().map(|x| x as i64)
+        match self {
+            Value::Int(x) => Some(*x),
+            Value::Number(x) => Some(*x as i64),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Bool(x) => Some(*x),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(string) => Some(string),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_list(&self) -> Option<&Vec<Value>> {
+        match self {
+            Value::List(list) => Some(list),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_list_mut(&mut self) -> Option<&mut Vec<Value>> {
+        match self {
+            Value::List(list) => Some(list),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn is_nil(&self) -> bool {
+        matches!(self, Value::Nil)
+    }
+
+    /// Returns whether the value counts as true in a boolean context:
+    /// `false`, `nil`, zero, NaN, the empty string and the empty list are false, every other value is true.
+    ///
+    /// ```
+    /// # use stackline::prelude::*;
+    /// assert!(Value::from(-1).is_truthy());
+    /// assert!(Value::from("a").is_truthy());
+    /// assert!(!Value::from(0.0).is_truthy());
+    /// assert!(!Value::Nil.is_truthy());
+    /// assert!(!Value::List(vec![]).is_truthy());
+    /// ```
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            Value::Number(x) => *x != 0.0 && !x.is_nan(),
+            Value::String(string) => !string.is_empty(),
+            Value::Int(x) => *x != 0,
+            Value::Bool(x) => *x,
+            Value::Nil => false,
+            Value::List(list) => !list.is_empty(),
+        }
+    }
+
+    /// Returns the name of the type of the value: `"number"`, `"string"`, `"int"`, `"bool"`, `"nil"` or `"list"`.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Number(_) => "number",
+            Value::String(_) => "string",
+            Value::Int(_) => "int",
+            Value::Bool(_) => "bool",
+            Value::Nil => "nil",
+            Value::List(_) => "list",
+        }
     }
 }
 
@@ -28,9 +133,33 @@ impl From<f64> for Value {
     }
 }
 
+impl From<i64> for Value {
+    fn from(x: i64) -> Value {
+        Value::Int(x)
+    }
+}
+
+impl From<i32> for Value {
+    fn from(x: i32) -> Value {
+        Value::Int(x.into())
+    }
+}
+
 impl From<u32> for Value {
     fn from(x: u32) -> Value {
-        Value::Number(x.into())
+        Value::Int(x.into())
+    }
+}
+
+impl From<bool> for Value {
+    fn from(x: bool) -> Value {
+        Value::Bool(x)
+    }
+}
+
+impl From<()> for Value {
+    fn from(_: ()) -> Value {
+        Value::Nil
     }
 }
 
@@ -46,8 +175,57 @@ impl<'a> From<&'a str> for Value {
     }
 }
 
+impl<T: Into<Value>> From<Vec<T>> for Value {
+    fn from(list: Vec<T>) -> Value {
+        Value::List(list.into_iter().map(Into::into).collect())
+    }
+}
+
+/// Formats the value as text: strings are written as-is, [`Nil`](Value::Nil) is written as `nil`
+/// and lists are written between brackets, with the strings in them being quoted.
+///
+/// If a precision is given, then numbers and integers are written with that many digits after the decimal point.
+///
+/// ```
+/// # use stackline::prelude::*;
+/// assert_eq!(Value::from(1.5).to_string(), "1.5");
+/// assert_eq!(format!("{:.2}", Value::from(2)), "2.00");
+/// assert_eq!(format!("{:.1}", Value::from(i64::MAX)), "9223372036854775807.0");
+/// assert_eq!(Value::from(vec![Value::from("a"), Value::Nil]).to_string(), "[\"a\", nil]");
+/// ```
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match (self, f.precision()) {
+            (Value::Number(x), Some(precision)) => write!(f, "{:.*}", precision, x),
+            (Value::Int(x), Some(0)) => write!(f, "{}", x),
+            (Value::Int(x), Some(precision)) => write!(f, "{}.{}", x, "0".repeat(precision)),
+            (Value::Number(x), None) => write!(f, "{}", x),
+            (Value::Int(x), None) => write!(f, "{}", x),
+            (Value::String(string), _) => write!(f, "{}", string),
+            (Value::Bool(x), _) => write!(f, "{}", x),
+            (Value::Nil, _) => write!(f, "nil"),
+            (Value::List(list), _) => {
+                write!(f, "[")?;
+                for (index, value) in list.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    match value {
+                        Value::String(string) => write!(f, "{:?}", string)?,
+                        value => std::fmt::Display::fmt(value, f)?,
+                    }
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
 /// The unit of information that [`Tile`]s transmit between each other.
-/// A `Signal` is made up of a [`stack`](Signal::stack), and tracks its [`position`](Signal::position) and [`direction`](Signal::direction).
+/// A `Signal` is made up of a [`stack`](Signal::stack) and of a [`heap`](Signal::heap) of named values,
+/// and tracks its [`position`](Signal::position) and [`direction`](Signal::direction).
+///
+/// When an operation fails on a signal, the signal is marked as [errored](Signal::set_error) instead of being dropped.
 ///
 /// ## Creating a signal
 ///
@@ -61,14 +239,26 @@ pub struct Signal {
     direction: Direction,
     position: (usize, usize),
     stack: Vec<Value>,
+    #[serde(default)]
+    heap: BTreeMap<String, Value>,
+    #[serde(default)]
+    return_addresses: Vec<(String, usize, usize)>,
+    #[serde(default)]
+    error: Option<String>,
 }
 
 impl Signal {
+    /// Name of the heap variable holding the names of the panes spawned for the signal, see [`push_spawned`](Signal::push_spawned)
+    pub const SPAWNED_VAR: &'static str = "#spawned";
+
     pub fn empty(position: (usize, usize), direction: Direction) -> Self {
         Self {
             direction,
             position,
             stack: Vec::new(),
+            heap: BTreeMap::new(),
+            return_addresses: Vec::new(),
+            error: None,
         }
     }
 
@@ -164,6 +354,40 @@ impl Signal {
         self.stack.pop()
     }
 
+    /// Replaces the top `count` values of the stack with the result of `operation`, which is given these values,
+    /// from the bottom-most to the top-most one. If the stack holds fewer than `count` values, then `operation` is given the whole stack.
+    ///
+    /// If `operation` returns an error, then the stack is left untouched and the signal is [marked as errored](Signal::set_error).
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use stackline::prelude::*;
+    /// let mut signal = stackline::signal!((0, 0), [1, 2, 3]);
+    ///
+    /// signal.apply_to_stack(2, |values| Ok(Value::List(values.to_vec())));
+    /// assert_eq!(signal.stack(), &vec![Value::Int(1), Value::from(vec![2, 3])]);
+    ///
+    /// signal.apply_to_stack(3, |values| Err(format!("expected 3 values, found {}", values.len())));
+    /// assert_eq!(signal.len(), 2);
+    /// assert_eq!(signal.error(), Some("expected 3 values, found 2"));
+    /// ```
+    pub fn apply_to_stack(
+        &mut self,
+        count: usize,
+        operation: impl FnOnce(&[Value]) -> Result<Value, String>,
+    ) {
+        let start = self.stack.len().saturating_sub(count);
+
+        match operation(&self.stack[start..]) {
+            Ok(value) => {
+                self.stack.truncate(start);
+                self.stack.push(value);
+            }
+            Err(message) => self.set_error(message),
+        }
+    }
+
     /// Returns the number of elements in the stack of the signal.
     ///
     /// ## Example
@@ -174,7 +398,7 @@ impl Signal {
     ///
     /// assert_eq!(signal.len(), 2);
     /// ```
-    #[allow(clippy::len_without_is_empty)] // The signal will hold a `heap` in the future, making `is_empty` ambiguous
+    #[allow(clippy::len_without_is_empty)] // The signal also holds a `heap`, making `is_empty` ambiguous
     pub fn len(&self) -> usize {
         self.stack.len()
     }
@@ -190,6 +414,168 @@ impl Signal {
     pub fn stack_mut(&mut self) -> &mut Vec<Value> {
         &mut self.stack
     }
+
+    /// Sets the value named `key` in the heap of the signal to `value`,
+    /// returning the previous value stored under that name, if any.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use stackline::prelude::*;
+    /// let mut signal = Signal::empty((0, 0), Direction::Down);
+    ///
+    /// assert_eq!(signal.set_var("x", Value::Number(1.0)), None);
+    /// assert_eq!(signal.set_var("x", Value::Number(2.0)), Some(Value::Number(1.0)));
+    ///
+    /// assert_eq!(signal.get_var("x"), Some(&Value::Number(2.0)));
+    /// assert_eq!(signal.get_var("y"), None);
+    ///
+    /// // The heap is kept when the signal is moved
+    /// let signal = signal.clone_move(Direction::Left);
+    /// assert_eq!(signal.get_var("x"), Some(&Value::Number(2.0)));
+    /// ```
+    pub fn set_var(&mut self, key: impl Into<String>, value: Value) -> Option<Value> {
+        self.heap.insert(key.into(), value)
+    }
+
+    /// Returns the value named `key` in the heap of the signal, if any.
+    pub fn get_var(&self, key: &str) -> Option<&Value> {
+        self.heap.get(key)
+    }
+
+    /// Removes the value named `key` from the heap of the signal, returning it if it was present.
+    pub fn remove_var(&mut self, key: &str) -> Option<Value> {
+        self.heap.remove(key)
+    }
+
+    pub fn heap(&self) -> &BTreeMap<String, Value> {
+        &self.heap
+    }
+
+    pub fn heap_mut(&mut self) -> &mut BTreeMap<String, Value> {
+        &mut self.heap
+    }
+
+    /// Pushes a return address, made up of a pane name and of coordinates within that pane, onto the signal.
+    /// Return addresses are stored separately from the [`stack`](Signal::stack), so that calls may be nested.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use stackline::prelude::*;
+    /// let mut signal = Signal::empty((0, 0), Direction::Down);
+    ///
+    /// signal.push_return((String::from("main"), 1, 2));
+    /// signal.push_return((String::from("sub"), 0, 0));
+    ///
+    /// assert_eq!(signal.pop_return(), Some((String::from("sub"), 0, 0)));
+    /// assert_eq!(signal.pop_return(), Some((String::from("main"), 1, 2)));
+    /// assert_eq!(signal.pop_return(), None);
+    /// ```
+    pub fn push_return(&mut self, coordinates: (String, usize, usize)) {
+        self.return_addresses.push(coordinates);
+    }
+
+    /// Pops the last return address pushed with [`push_return`](Signal::push_return), if any.
+    pub fn pop_return(&mut self) -> Option<(String, usize, usize)> {
+        self.return_addresses.pop()
+    }
+
+    pub fn return_addresses(&self) -> &Vec<(String, usize, usize)> {
+        &self.return_addresses
+    }
+
+    /// Records that the pane named `name` was spawned for this signal, so that it can later be deleted
+    /// by a [`Despawn`](crate::tile::Despawn) tile.
+    ///
+    /// The names are kept in the heap of the signal, as a [list](Value::List) named [`SPAWNED_VAR`](Signal::SPAWNED_VAR),
+    /// apart from the [return addresses](Signal::push_return) used by [`Call`](crate::tile::Call) and [`Return`](crate::tile::Return).
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use stackline::prelude::*;
+    /// let mut signal = Signal::empty((0, 0), Direction::Down);
+    ///
+    /// signal.push_spawned(String::from("copy#0"));
+    /// signal.push_spawned(String::from("copy#1"));
+    /// assert!(signal.return_addresses().is_empty());
+    ///
+    /// assert_eq!(signal.pop_spawned(), Some(String::from("copy#1")));
+    /// assert_eq!(signal.pop_spawned(), Some(String::from("copy#0")));
+    /// assert_eq!(signal.pop_spawned(), None);
+    /// assert!(signal.heap().is_empty());
+    /// ```
+    pub fn push_spawned(&mut self, name: String) {
+        let spawned = self
+            .heap
+            .entry(String::from(Self::SPAWNED_VAR))
+            .or_insert_with(|| Value::List(Vec::new()));
+
+        match spawned {
+            Value::List(list) => list.push(Value::String(name)),
+            value => *value = Value::List(vec![Value::String(name)]),
+        }
+    }
+
+    /// Pops the name of the last pane pushed with [`push_spawned`](Signal::push_spawned), if any.
+    /// The heap variable holding the names is removed once it is empty.
+    pub fn pop_spawned(&mut self) -> Option<String> {
+        let (name, empty) = match self.heap.get_mut(Self::SPAWNED_VAR) {
+            Some(Value::List(list)) => (list.pop(), list.is_empty()),
+            _ => (None, false),
+        };
+
+        if empty {
+            self.heap.remove(Self::SPAWNED_VAR);
+        }
+
+        match name {
+            Some(Value::String(name)) => Some(name),
+            _ => None,
+        }
+    }
+
+    /// Marks the signal as errored, with `message` describing the failed operation.
+    /// Tiles should call this method instead of dropping the signal when an operation fails on it,
+    /// so that the error may be seen and routed, for instance with a [`Catch`](crate::tile::Catch) tile.
+    ///
+    /// If the signal is already errored, then the first error is kept, as it is the one that caused the others.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use stackline::prelude::*;
+    /// let mut signal = Signal::empty((0, 0), Direction::Down);
+    /// assert!(!signal.is_errored());
+    ///
+    /// signal.set_error("Division by zero");
+    /// signal.set_error("Type mismatch");
+    /// assert_eq!(signal.error(), Some("Division by zero"));
+    ///
+    /// assert_eq!(signal.take_error(), Some(String::from("Division by zero")));
+    /// assert!(!signal.is_errored());
+    /// ```
+    pub fn set_error(&mut self, message: impl Into<String>) {
+        if self.error.is_none() {
+            self.error = Some(message.into());
+        }
+    }
+
+    /// Returns the error message of the signal, if it is errored.
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    #[inline]
+    pub fn is_errored(&self) -> bool {
+        self.error.is_some()
+    }
+
+    /// Clears the error of the signal, returning its message if the signal was errored.
+    pub fn take_error(&mut self) -> Option<String> {
+        self.error.take()
+    }
 }
 
 /// Creates a signal with initial values in its stack.
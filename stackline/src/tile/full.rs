@@ -31,7 +31,10 @@ impl FullTile {
 
     pub fn accepts_signal(&self, direction: Direction) -> bool {
         match self.cell {
-            Some(ref tile) => self.state.accepts_signal() && tile.accepts_signal(direction),
+            Some(ref tile) => {
+                (self.state.accepts_signal() || tile.accepts_while_active())
+                    && tile.accepts_signal(direction)
+            }
             None => false,
         }
     }
@@ -46,6 +49,20 @@ impl FullTile {
         }
     }
 
+    /// Gives `signal` to the tile, which may hold onto it through [`Tile::absorb_signal`].
+    /// Otherwise, the signal is stored in the tile, like with [`set_signal`](FullTile::set_signal).
+    ///
+    /// Returns `Some` iff self.cell.is_some()
+    pub fn receive_signal(&mut self, signal: Signal) -> Option<()> {
+        let cell = self.cell.as_mut()?;
+
+        if let Some(signal) = cell.absorb_signal(signal) {
+            self.signal = Some(signal);
+        }
+
+        Some(())
+    }
+
     /// Returns the internal state of this full tile
     #[inline]
     pub fn get(&self) -> Option<&AnyTile> {
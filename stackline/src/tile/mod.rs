@@ -154,6 +154,24 @@ pub trait Tile: std::clone::Clone + std::fmt::Debug + Serialize + for<'d> Deseri
         true
     }
 
+    /// Called when a signal is sent to the tile, once every tile of the [`Pane`] was updated.
+    /// Tiles that hold several signals at once may store `signal` themselves and return `None`.
+    ///
+    /// By default, `signal` is returned as-is and then stored in the tile, replacing the signal that it held, if any.
+    #[inline]
+    fn absorb_signal(&mut self, signal: Signal) -> Option<Signal> {
+        Some(signal)
+    }
+
+    /// Should return true iff the tile accepts signals while it is [`Active`](State::Active) or [`Dormant`](State::Dormant).
+    /// By default, tiles only accept signals while they are [`Idle`](State::Idle).
+    ///
+    /// Tiles that stay active while they process the signals they received, like [`Chip`], may use this to keep receiving signals.
+    #[inline]
+    fn accepts_while_active(&self) -> bool {
+        false
+    }
+
     /// Should draw itself on a [`TextSurface`].
     /// The `Tile` is allowed to draw outside of its coordinates, although doing so might cause glitches.
     ///
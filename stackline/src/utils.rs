@@ -11,7 +11,7 @@ pub enum Orientation {
 }
 
 /// Represents one directed orientation
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[repr(u8)]
 pub enum Direction {
     Up,
@@ -92,6 +92,28 @@ impl Direction {
             Direction::Right => Direction::Left,
         }
     }
+
+    /// Rotates the direction by 90 degrees counter-clockwise, with `Up` becoming `Left`
+    #[inline]
+    pub fn rotate_left(self) -> Self {
+        match self {
+            Direction::Up => Direction::Left,
+            Direction::Left => Direction::Down,
+            Direction::Down => Direction::Right,
+            Direction::Right => Direction::Up,
+        }
+    }
+
+    /// Rotates the direction by 90 degrees clockwise, with `Up` becoming `Right`
+    #[inline]
+    pub fn rotate_right(self) -> Self {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
 }
 
 impl Default for Direction {
@@ -143,6 +165,21 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_direction_rotate() {
+        for direction in ANY {
+            assert_eq!(direction.rotate_left().rotate_right(), direction);
+            assert_eq!(direction.rotate_left().rotate_left(), direction.opposite());
+            assert_eq!(
+                direction.rotate_right().rotate_right(),
+                direction.opposite()
+            );
+        }
+
+        assert_eq!(Direction::Up.rotate_left(), Direction::Left);
+        assert_eq!(Direction::Up.rotate_right(), Direction::Right);
+    }
+
     #[test]
     fn test_state_next_rotate_3() {
         let state = State::default();
@@ -1,142 +1,394 @@
 use super::*;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use veccell::{VecRef, VecRefMut};
 
+/// Side length of the square chunks used by the spatial index of [`World`].
+const CHUNK_SIZE: i32 = 16;
+
+/// A set of named [`Pane`]s, placed in a shared coordinate space.
+///
+/// Panes may overlap: their stacking order, from the bottom-most to the top-most pane, is given by [`World::pane_order`].
+/// Lookups in world coordinates, like [`World::get`], return the tile of the top-most pane at that position,
+/// and [`World::draw`] draws the panes from bottom to top, so that what is drawn matches what is returned by [`World::get`].
+///
+/// A world also holds global variables, which tiles may read and write through [`UpdateContext::get_global`] and [`UpdateContext::set_global`],
+/// and the [seed](World::seed) of the pseudo-random numbers returned by [`UpdateContext::random`].
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(from = "WorldData")]
 pub struct World {
     panes: HashMap<String, Pane>,
+
+    /// Names of the panes, from the bottom-most to the top-most one
+    order: Vec<String>,
+
+    globals: BTreeMap<String, Value>,
+
+    /// Seed of the pseudo-random numbers given to the tiles
+    seed: u64,
+
+    /// Number of update cycles since the world was created or since its seed was set
+    steps: u64,
+
+    /// Number of update cycles since the world was created; unlike `steps`, it is never reset,
+    /// so that the names given by [`UpdateContext::unique_pane_name`] stay unique
+    age: u64,
+
+    #[serde(skip)]
+    index: RefCell<Option<PaneIndex>>,
 }
 
 impl World {
     pub fn new() -> Self {
         Self {
             panes: HashMap::new(),
+            order: Vec::new(),
+            globals: BTreeMap::new(),
+            seed: 0,
+            steps: 0,
+            age: 0,
+            index: RefCell::new(None),
         }
     }
 
+    /// Performs an update cycle on every pane, from the bottom-most to the top-most one,
+    /// then writes the global variables, sends the outbound signals to their target pane and the signals that left a pane through its edges
+    /// to the pane found at their destination.
     pub fn step(&mut self) {
         let mut outbound_signals = Vec::new();
+        let mut edge_signals = Vec::new();
+        let mut pane_operations = Vec::new();
+        let mut global_writes = Vec::new();
+
+        let edges = self.accepting_edges();
+        let env = UpdateEnv {
+            globals: &self.globals,
+            seed: self.seed,
+            step: self.steps,
+            age: self.age,
+            scope: 0,
+            edges: Some(&edges),
+        };
+
+        for name in self.order.iter() {
+            if let Some(pane) = self.panes.get_mut(name) {
+                let mut res = pane.step_with(env);
+                outbound_signals.append(&mut res.outbound_signals);
+                edge_signals.append(&mut res.edge_signals);
+                pane_operations.append(&mut res.pane_operations);
+                global_writes.append(&mut res.global_writes);
+            }
+        }
+
+        self.steps += 1;
+        self.age += 1;
 
-        for pane in self.panes.values_mut() {
-            let mut res = pane.step();
-            outbound_signals.append(&mut res.outbound_signals);
+        for (key, value) in global_writes {
+            match value {
+                Some(value) => self.globals.insert(key, value),
+                None => self.globals.remove(&key),
+            };
+        }
+
+        for operation in pane_operations {
+            self.apply_operation(operation);
         }
 
         for ((name, x, y), signal) in outbound_signals {
-            if let Some(pane) = self.get_pane_mut(&name) {
+            if let Some(pane) = self.panes.get_mut(&name) {
                 let _ = pane.set_signal((x, y), signal); // Errors are ignored
             }
         }
-    }
 
-    pub fn set_pane(&mut self, name: String, pane: Pane) {
-        self.panes.insert(name, pane);
-    }
-
-    pub fn get(&self, (x, y): (i32, i32)) -> Option<VecRef<'_, FullTile>> {
-        for pane in self.panes.values() {
-            let x2 = x - pane.position().0;
-            let y2 = y - pane.position().1;
-            if x2 >= 0
-                && x2 < pane.width().get() as i32
-                && y2 >= 0
-                && y2 < pane.height().get() as i32
-            {
-                let x2 = x2 as usize;
-                let y2 = y2 as usize;
-                if let Some(tile) = pane.get((x2, y2)) {
-                    return Some(tile);
+        for (position, signal) in edge_signals {
+            // Signals are only received by tiles that accepted them when the panes were updated
+            if !edges.contains(&(position, signal.direction())) {
+                continue;
+            }
+
+            let name = match self.pane_at(position) {
+                Some((name, _)) => name.to_string(),
+                None => continue,
+            };
+
+            if let Some(pane) = self.panes.get_mut(&name) {
+                if let Some(local) = local_position(pane, position) {
+                    let _ = pane.set_signal(local, signal);
                 }
             }
         }
-        None
     }
 
-    pub fn get_with_pos(&self, (x, y): (i32, i32)) -> Option<(VecRef<'_, FullTile>, usize, usize)> {
-        for pane in self.panes.values() {
-            let x2 = x - pane.position().0;
-            let y2 = y - pane.position().1;
-            if x2 >= 0
-                && x2 < pane.width().get() as i32
-                && y2 >= 0
-                && y2 < pane.height().get() as i32
-            {
-                let x2 = x2 as usize;
-                let y2 = y2 as usize;
-                if let Some(tile) = pane.get((x2, y2)) {
-                    return Some((tile, x2, y2));
+    /// Returns the destinations of the signals that may leave the connected panes through their edges, alongside their direction,
+    /// for which the tile found at the destination currently accepts such signals.
+    fn accepting_edges(&self) -> HashSet<((i32, i32), Direction)> {
+        self.panes_ordered()
+            .flat_map(|pane| pane.edge_destinations())
+            .filter(|&(position, direction)| {
+                self.get(position)
+                    .is_some_and(|tile| tile.accepts_signal(direction))
+            })
+            .collect()
+    }
+
+    /// Inserts `pane` under `name`. If a pane named `name` already exists, then it is replaced
+    /// and the new pane keeps its place in the stacking order. Otherwise, the new pane is placed on top of the others.
+    pub fn set_pane(&mut self, name: String, pane: Pane) {
+        if self.panes.insert(name.clone(), pane).is_none() {
+            self.order.push(name);
+        }
+        self.invalidate_index();
+    }
+
+    /// Returns the seed of the pseudo-random numbers that tiles get through [`UpdateContext::random`].
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Sets the seed of the pseudo-random numbers that tiles get through [`UpdateContext::random`],
+    /// and resets the number of update cycles that the world went through, so that the following steps
+    /// yield the same numbers for a given seed.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.steps = 0;
+    }
+
+    /// Returns the number of update cycles since the world was created or since its seed was [set](World::set_seed).
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    /// Returns the value of the global variable named `key`, if it exists.
+    pub fn get_global(&self, key: &str) -> Option<&Value> {
+        self.globals.get(key)
+    }
+
+    /// Sets the global variable named `key` to `value`, returning its previous value, if any.
+    pub fn set_global(&mut self, key: impl Into<String>, value: Value) -> Option<Value> {
+        self.globals.insert(key.into(), value)
+    }
+
+    /// Removes the global variable named `key`, returning its value if it existed.
+    pub fn remove_global(&mut self, key: &str) -> Option<Value> {
+        self.globals.remove(key)
+    }
+
+    pub fn globals(&self) -> &BTreeMap<String, Value> {
+        &self.globals
+    }
+
+    /// Removes the pane named `name` and returns it, if it exists.
+    pub fn remove_pane(&mut self, name: &str) -> Option<Pane> {
+        let pane = self.panes.remove(name)?;
+        self.order.retain(|n| n != name);
+        self.invalidate_index();
+
+        Some(pane)
+    }
+
+    /// Applies a [`PaneOperation`], as requested by a tile during [`World::step`].
+    /// Operations that cannot be carried out are ignored.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use stackline::prelude::*;
+    /// use stackline::world::PaneOperation;
+    ///
+    /// let mut world = World::new();
+    /// world.set_pane(String::from("template"), Pane::empty(2, 2).unwrap());
+    ///
+    /// world.apply_operation(PaneOperation::Instantiate {
+    ///     source: String::from("template"),
+    ///     name: String::from("copy"),
+    ///     position: (4, 0),
+    /// });
+    /// assert_eq!(world.get_pane("copy").unwrap().position(), (4, 0));
+    ///
+    /// world.apply_operation(PaneOperation::Delete {
+    ///     name: String::from("template"),
+    /// });
+    /// assert!(world.get_pane("template").is_none());
+    /// ```
+    pub fn apply_operation(&mut self, operation: PaneOperation) {
+        match operation {
+            PaneOperation::Instantiate {
+                source,
+                name,
+                position,
+            } => {
+                if self.panes.contains_key(&name) {
+                    return;
+                }
+
+                if let Some(pane) = self.panes.get(&source) {
+                    let mut pane = pane.clone();
+                    pane.set_position(position);
+                    self.set_pane(name, pane);
                 }
             }
+            PaneOperation::Delete { name } => {
+                self.remove_pane(&name);
+            }
         }
-        None
     }
 
-    pub fn get_mut(&mut self, (x, y): (i32, i32)) -> Option<&mut FullTile> {
-        for pane in self.panes.values_mut() {
-            let x2 = x - pane.position().0;
-            let y2 = y - pane.position().1;
-            if x2 >= 0
-                && x2 < pane.width().get() as i32
-                && y2 >= 0
-                && y2 < pane.height().get() as i32
-            {
-                let x2 = x2 as usize;
-                let y2 = y2 as usize;
-                if let Some(tile) = pane.get_mut((x2, y2)) {
-                    return Some(tile);
-                }
+    /// Returns the names of the panes, from the bottom-most to the top-most one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use stackline::prelude::*;
+    /// let mut world = World::new();
+    ///
+    /// world.set_pane(String::from("first"), Pane::empty(2, 2).unwrap());
+    /// world.set_pane(String::from("second"), Pane::empty(2, 2).unwrap());
+    ///
+    /// assert_eq!(world.pane_order(), ["first", "second"]);
+    ///
+    /// world.lower_pane("second");
+    /// assert_eq!(world.pane_order(), ["second", "first"]);
+    /// ```
+    #[inline]
+    pub fn pane_order(&self) -> &[String] {
+        &self.order
+    }
+
+    /// Moves the pane named `name` above every other pane.
+    /// Returns `None` if there is no such pane.
+    pub fn raise_pane(&mut self, name: &str) -> Option<()> {
+        let index = self.order.iter().position(|n| n == name)?;
+        let name = self.order.remove(index);
+        self.order.push(name);
+        self.invalidate_index();
+
+        Some(())
+    }
+
+    /// Moves the pane named `name` below every other pane.
+    /// Returns `None` if there is no such pane.
+    pub fn lower_pane(&mut self, name: &str) -> Option<()> {
+        let index = self.order.iter().position(|n| n == name)?;
+        let name = self.order.remove(index);
+        self.order.insert(0, name);
+        self.invalidate_index();
+
+        Some(())
+    }
+
+    /// Returns the name of the top-most pane containing `(x, y)`, alongside that pane.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use stackline::prelude::*;
+    /// let mut world = World::new();
+    ///
+    /// world.set_pane(String::from("bottom"), Pane::empty(4, 4).unwrap());
+    ///
+    /// let mut top = Pane::empty(2, 2).unwrap();
+    /// top.set_position((1, 1));
+    /// world.set_pane(String::from("top"), top);
+    ///
+    /// assert_eq!(world.pane_at((0, 0)).unwrap().0, "bottom");
+    /// assert_eq!(world.pane_at((1, 1)).unwrap().0, "top");
+    /// assert!(world.pane_at((4, 4)).is_none());
+    /// ```
+    pub fn pane_at(&self, (x, y): (i32, i32)) -> Option<(&str, &Pane)> {
+        let mut index = self.index.borrow_mut();
+        let index = index.get_or_insert_with(|| PaneIndex::new(&self.order, &self.panes));
+
+        index
+            .get(x, y)
+            .iter()
+            .map(|&i| self.order[i].as_str())
+            .find_map(|name| {
+                let pane = self.panes.get(name)?;
+                local_position(pane, (x, y)).map(|_| (name, pane))
+            })
+    }
+
+    /// Returns `(x + Δx, y + Δy)`, where `offset = (Δx, Δy)`.
+    /// If the top-most pane at `(x, y)` [wraps around](Pane::set_wrap) and `(x + Δx, y + Δy)` falls outside of it,
+    /// then the returned coordinates wrap around within that pane instead.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use stackline::prelude::*;
+    /// let mut pane = Pane::empty(4, 1).unwrap();
+    /// pane.set_position((1, 0));
+    /// pane.set_wrap(true, false);
+    ///
+    /// let mut world = World::new();
+    /// world.set_pane(String::from("main"), pane);
+    ///
+    /// assert_eq!(world.offset((4, 0), (1, 0)), (1, 0));
+    /// assert_eq!(world.offset((4, 0), (0, 1)), (4, 1));
+    /// assert_eq!(world.offset((5, 0), (1, 0)), (6, 0));
+    /// ```
+    pub fn offset(&self, (x, y): (i32, i32), offset: (i8, i8)) -> (i32, i32) {
+        if let Some((_, pane)) = self.pane_at((x, y)) {
+            let new_pos = local_position(pane, (x, y)).and_then(|pos| pane.offset(pos, offset));
+
+            if let Some((x2, y2)) = new_pos {
+                return (pane.position().0 + x2 as i32, pane.position().1 + y2 as i32);
             }
         }
-        None
+
+        (x + offset.0 as i32, y + offset.1 as i32)
+    }
+
+    pub fn get(&self, position: (i32, i32)) -> Option<VecRef<'_, FullTile>> {
+        self.get_with_pos(position).map(|(tile, _, _)| tile)
+    }
+
+    pub fn get_with_pos(
+        &self,
+        position: (i32, i32),
+    ) -> Option<(VecRef<'_, FullTile>, usize, usize)> {
+        let (_, pane) = self.pane_at(position)?;
+        let (x2, y2) = local_position(pane, position)?;
+
+        pane.get((x2, y2)).map(|tile| (tile, x2, y2))
+    }
+
+    pub fn get_mut(&mut self, position: (i32, i32)) -> Option<&mut FullTile> {
+        self.get_mut_with_pos(position).map(|(tile, _, _)| tile)
     }
 
     pub fn get_mut_with_pos(
         &mut self,
-        (x, y): (i32, i32),
+        position: (i32, i32),
     ) -> Option<(&mut FullTile, usize, usize)> {
-        for pane in self.panes.values_mut() {
-            let x2 = x - pane.position().0;
-            let y2 = y - pane.position().1;
-            if x2 >= 0
-                && x2 < pane.width().get() as i32
-                && y2 >= 0
-                && y2 < pane.height().get() as i32
-            {
-                let x2 = x2 as usize;
-                let y2 = y2 as usize;
-                if let Some(tile) = pane.get_mut((x2, y2)) {
-                    return Some((tile, x2, y2));
-                }
-            }
-        }
-        None
+        let name = self.pane_at(position)?.0.to_string();
+        let pane = self.panes.get_mut(&name)?;
+        let (x2, y2) = local_position(pane, position)?;
+
+        pane.get_mut((x2, y2)).map(|tile| (tile, x2, y2))
     }
 
     pub fn get_pane(&self, name: &str) -> Option<&Pane> {
         self.panes.get(name)
     }
 
+    /// Returns a mutable reference to the pane named `name`.
+    ///
+    /// Since the pane may be moved or resized through that reference, the spatial index of the `World`
+    /// will be rebuilt on the next lookup in world coordinates.
     pub fn get_pane_mut(&mut self, name: &str) -> Option<&mut Pane> {
+        self.invalidate_index();
         self.panes.get_mut(name)
     }
 
     pub fn in_pane(&self, x: i32, y: i32) -> bool {
-        for pane in self.panes.values() {
-            if x >= pane.position().0
-                && y >= pane.position().1
-                && x < pane.position().0 + pane.width().get() as i32
-                && y < pane.position().1 + pane.height().get() as i32
-            {
-                return true;
-            }
-        }
-
-        false
+        self.pane_at((x, y)).is_some()
     }
 
+    /// Draws the panes on `surface`, from the bottom-most to the top-most one.
     pub fn draw(&self, dx: i32, dy: i32, surface: &mut TextSurface) {
-        for pane in self.panes.values() {
+        for pane in self.panes_ordered() {
             pane.draw(dx, dy, surface);
         }
     }
@@ -155,6 +407,136 @@ impl World {
     pub fn panes(&self) -> &HashMap<String, Pane> {
         &self.panes
     }
+
+    /// Returns an iterator over the panes, from the bottom-most to the top-most one.
+    fn panes_ordered(&self) -> impl Iterator<Item = &Pane> + '_ {
+        self.order.iter().filter_map(|name| self.panes.get(name))
+    }
+
+    #[inline]
+    fn invalidate_index(&mut self) {
+        *self.index.get_mut() = None;
+    }
+}
+
+/// An operation on the panes of a [`World`], which tiles may request through [`UpdateContext::instantiate_pane`]
+/// and [`UpdateContext::delete_pane`]. These operations are [applied](World::apply_operation) after every pane was updated,
+/// in the stacking order of the panes that requested them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PaneOperation {
+    /// Inserts a copy of the pane named `source` at `position`, under the name `name`, on top of the other panes
+    Instantiate {
+        source: String,
+        name: String,
+        position: (i32, i32),
+    },
+    /// Removes the pane named `name`
+    Delete { name: String },
+}
+
+/// Returns the coordinates of `(x, y)` relative to `pane`, if `(x, y)` lies within `pane`.
+#[inline]
+fn local_position(pane: &Pane, (x, y): (i32, i32)) -> Option<(usize, usize)> {
+    let x2 = x - pane.position().0;
+    let y2 = y - pane.position().1;
+
+    if x2 >= 0 && x2 < pane.width().get() as i32 && y2 >= 0 && y2 < pane.height().get() as i32 {
+        Some((x2 as usize, y2 as usize))
+    } else {
+        None
+    }
+}
+
+/// Spatial index of the panes of a [`World`], which buckets them in square chunks of [`CHUNK_SIZE`] tiles.
+#[derive(Debug, Default)]
+struct PaneIndex {
+    /// For each chunk, the indices (in `World::order`) of the panes overlapping it, from the top-most to the bottom-most one
+    chunks: HashMap<(i32, i32), Vec<usize>>,
+}
+
+impl PaneIndex {
+    fn new(order: &[String], panes: &HashMap<String, Pane>) -> Self {
+        let mut res = Self::default();
+
+        for (index, name) in order.iter().enumerate().rev() {
+            let pane = match panes.get(name) {
+                Some(pane) => pane,
+                None => continue,
+            };
+
+            let (x, y) = pane.position();
+            let x_max = x.saturating_add(pane.width().get() as i32 - 1);
+            let y_max = y.saturating_add(pane.height().get() as i32 - 1);
+
+            for cy in y.div_euclid(CHUNK_SIZE)..=y_max.div_euclid(CHUNK_SIZE) {
+                for cx in x.div_euclid(CHUNK_SIZE)..=x_max.div_euclid(CHUNK_SIZE) {
+                    res.chunks.entry((cx, cy)).or_default().push(index);
+                }
+            }
+        }
+
+        res
+    }
+
+    /// Returns the candidate panes for `(x, y)`, from the top-most to the bottom-most one
+    fn get(&self, x: i32, y: i32) -> &[usize] {
+        self.chunks
+            .get(&(x.div_euclid(CHUNK_SIZE), y.div_euclid(CHUNK_SIZE)))
+            .map(|v| v.as_slice())
+            .unwrap_or(&[])
+    }
+}
+
+/// Serialized form of [`World`]; worlds saved before the stacking order was introduced are stacked by name.
+#[derive(Deserialize)]
+struct WorldData {
+    panes: HashMap<String, Pane>,
+
+    #[serde(default)]
+    order: Vec<String>,
+
+    #[serde(default)]
+    globals: BTreeMap<String, Value>,
+
+    #[serde(default)]
+    seed: u64,
+
+    #[serde(default)]
+    steps: u64,
+
+    #[serde(default)]
+    age: u64,
+}
+
+impl From<WorldData> for World {
+    fn from(data: WorldData) -> Self {
+        let mut order: Vec<String> = Vec::with_capacity(data.panes.len());
+
+        for name in data.order {
+            if data.panes.contains_key(&name) && !order.contains(&name) {
+                order.push(name);
+            }
+        }
+
+        let mut missing: Vec<String> = data
+            .panes
+            .keys()
+            .filter(|name| !order.contains(name))
+            .cloned()
+            .collect();
+        missing.sort();
+        missing.append(&mut order);
+
+        Self {
+            panes: data.panes,
+            order: missing,
+            globals: data.globals,
+            seed: data.seed,
+            steps: data.steps,
+            age: data.age,
+            index: RefCell::new(None),
+        }
+    }
 }
 
 impl std::fmt::Display for World {
@@ -165,7 +547,7 @@ impl std::fmt::Display for World {
 
         let mut surface = TextSurface::new(width, height);
 
-        for pane in self.panes.values() {
+        for pane in self.panes_ordered() {
             pane.draw(bounds.0, bounds.2, &mut surface);
         }
 
@@ -191,4 +573,130 @@ mod test {
 
         assert!(world.get_pane("main").is_some());
     }
+
+    #[test]
+    fn test_world_overlap() {
+        use crate::tile::Wire;
+
+        let mut bottom = Pane::empty(40, 40).unwrap();
+        bottom.set_position((-20, -20));
+        bottom.set_tile((20, 20), Wire::new(Orientation::Horizontal));
+
+        let mut top = Pane::empty(1, 1).unwrap();
+        top.set_tile((0, 0), Wire::new(Orientation::Vertical));
+
+        let mut world = World::new();
+        world.set_pane(String::from("bottom"), bottom);
+        world.set_pane(String::from("top"), top);
+
+        let get_char = |world: &World| {
+            let mut surface = TextSurface::new(1, 1);
+            world.draw(0, 0, &mut surface);
+            surface.get(0, 0).unwrap().ch
+        };
+
+        assert_eq!(world.pane_at((0, 0)).unwrap().0, "top");
+        assert_eq!(
+            world
+                .get((0, 0))
+                .unwrap()
+                .get()
+                .unwrap()
+                .draw_simple(State::Idle)
+                .ch,
+            '|'
+        );
+        assert_eq!(get_char(&world), '|');
+        assert_eq!(world.pane_at((-20, 19)).unwrap().0, "bottom");
+        assert!(world.pane_at((-21, 0)).is_none());
+        assert!(world.pane_at((20, 0)).is_none());
+
+        world.raise_pane("bottom").unwrap();
+
+        assert_eq!(world.pane_order(), ["top", "bottom"]);
+        assert_eq!(world.pane_at((0, 0)).unwrap().0, "bottom");
+        assert_eq!(world.get_mut_with_pos((0, 0)).unwrap().1, 20);
+        assert_eq!(get_char(&world), '-');
+
+        // Moving a pane through get_pane_mut updates the index
+        world.get_pane_mut("top").unwrap().set_position((30, 30));
+        assert_eq!(world.pane_at((30, 30)).unwrap().0, "top");
+
+        assert!(world.raise_pane("missing").is_none());
+    }
+
+    #[test]
+    fn test_world_connected() {
+        use crate::tile::{Diode, Wire};
+        use Direction::*;
+
+        let mut left = test_tile_setup!(2, 1, [Diode::new(Right), Wire::new(Orientation::Any)]);
+        left.set_connected(true);
+
+        let mut right = test_tile_setup!(
+            2,
+            1,
+            [Wire::new(Orientation::Horizontal), Diode::new(Right)]
+        );
+        right.set_position((2, 0));
+
+        let mut below = test_tile_setup!(2, 1, [Diode::new(Right), ()]);
+        below.set_position((1, 1));
+
+        let mut world = World::new();
+        world.set_pane(String::from("left"), left);
+        world.set_pane(String::from("right"), right);
+        world.set_pane(String::from("below"), below);
+
+        world
+            .get_pane_mut("left")
+            .unwrap()
+            .set_signal((0, 0), Signal::empty((0, 0), Right));
+
+        world.step();
+        assert!(world.get((1, 0)).unwrap().signal().is_some());
+
+        // The wire sends the signal right, into "right", and down, into "below"
+        world.step();
+        assert!(world.get((1, 0)).unwrap().signal().is_none());
+        assert!(world.get((2, 0)).unwrap().signal().is_some());
+        assert!(world.get((1, 1)).unwrap().signal().is_some());
+        assert_eq!(
+            world.get((2, 0)).unwrap().signal().unwrap().position(),
+            (0, 0)
+        );
+
+        world.step();
+        assert!(world.get((3, 0)).unwrap().signal().is_some());
+        assert!(world.get((1, 1)).unwrap().signal().is_none());
+
+        // "right" isn't connected, so the signal is lost
+        world.step();
+        for (x, y) in [(0, 0), (1, 0), (2, 0), (3, 0), (1, 1), (2, 1)] {
+            assert!(world.get((x, y)).unwrap().signal().is_none());
+        }
+    }
+
+    #[test]
+    fn test_world_deserialize_order() {
+        let mut world = World::new();
+
+        world.set_pane(String::from("b"), Pane::empty(1, 1).unwrap());
+        world.set_pane(String::from("a"), Pane::empty(1, 1).unwrap());
+        world.set_pane(String::from("c"), Pane::empty(1, 1).unwrap());
+
+        let mut raw = serde_json::to_value(&world).unwrap();
+        let world: World = serde_json::from_value(raw.clone()).unwrap();
+        assert_eq!(world.pane_order(), ["b", "a", "c"]);
+
+        // Worlds without a stacking order have their panes stacked by name
+        raw.as_object_mut().unwrap().remove("order");
+        let world: World = serde_json::from_value(raw.clone()).unwrap();
+        assert_eq!(world.pane_order(), ["a", "b", "c"]);
+
+        // Unknown or duplicate names are ignored, and missing panes are put at the bottom
+        raw["order"] = serde_json::json!(["c", "d", "c", "a"]);
+        let world: World = serde_json::from_value(raw).unwrap();
+        assert_eq!(world.pane_order(), ["b", "c", "a"]);
+    }
 }
@@ -0,0 +1,112 @@
+//! Crossing tiles: let signals cross each other without mixing
+
+use crate::prelude::*;
+use crate::tile::prelude::*;
+
+/// Lets horizontal signals continue horizontally and vertical signals continue vertically,
+/// so that two wires may cross without their signals being mixed.
+///
+/// The bridge holds one horizontal and one vertical signal at once,
+/// so both signals go through even if they arrive during the same update cycle.
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+pub struct Bridge {
+    pub horizontal: Option<Signal>,
+    pub vertical: Option<Signal>,
+}
+
+impl Bridge {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Tile for Bridge {
+    fn update<'b>(&'b mut self, mut context: UpdateContext<'b>) {
+        // Signals are absorbed into `horizontal` and `vertical`, but may still be set directly on the tile
+        if let Some(signal) = context.take_signal() {
+            let _ = self.absorb_signal(signal);
+        }
+
+        for signal in [self.horizontal.take(), self.vertical.take()]
+            .into_iter()
+            .flatten()
+        {
+            let _ = context.send_direction(signal.direction(), signal);
+        }
+
+        if context.state() != State::Idle {
+            context.next_state();
+        }
+    }
+
+    fn absorb_signal(&mut self, signal: Signal) -> Option<Signal> {
+        if Orientation::Horizontal.contains(signal.direction()) {
+            self.horizontal = Some(signal);
+        } else {
+            self.vertical = Some(signal);
+        }
+
+        None
+    }
+
+    fn accepts_while_active(&self) -> bool {
+        true
+    }
+
+    fn draw_simple(&self, state: State) -> TextChar {
+        TextChar::from_state('\u{256c}', state) // Box Drawings Double Vertical and Horizontal
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_bridge() {
+        use crate::{Diode, Wire};
+        use Direction::*;
+        use Orientation::*;
+
+        let mut pane = test_tile_setup!(
+            3,
+            3,
+            [
+                (),
+                Diode::new(Down),
+                (),
+                Diode::new(Right),
+                Bridge::new(),
+                Wire::new(Horizontal),
+                (),
+                Wire::new(Vertical),
+                (),
+            ]
+        );
+
+        // Both signals arrive in the bridge during the same update cycle
+        pane.set_signal((0, 1), crate::signal!((0, 1), Right, [1]));
+        pane.set_signal((1, 0), crate::signal!((1, 0), Down, [2]));
+        pane.step();
+
+        let bridge = pane.get_as::<Bridge>((1, 1)).unwrap();
+        assert!(bridge.horizontal.is_some());
+        assert!(bridge.vertical.is_some());
+        drop(bridge);
+
+        pane.step();
+        assert_no_signal!(pane, (1, 1));
+        assert_eq!(
+            pane.get((2, 1)).unwrap().signal().unwrap().stack(),
+            &vec![Value::Int(1)]
+        );
+        assert_eq!(
+            pane.get((1, 2)).unwrap().signal().unwrap().stack(),
+            &vec![Value::Int(2)]
+        );
+
+        let bridge = pane.get_as::<Bridge>((1, 1)).unwrap();
+        assert!(bridge.horizontal.is_none());
+        assert!(bridge.vertical.is_none());
+    }
+}
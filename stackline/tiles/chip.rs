@@ -0,0 +1,294 @@
+//! Sub-circuits: tiles embedding a whole pane
+
+use crate::prelude::*;
+use crate::tile::prelude::*;
+use crate::world::PaneOperation;
+use std::collections::HashSet;
+
+/// A sub-circuit packaged as a single tile: the chip holds its own [`Pane`], which is stepped in lockstep with the outer pane.
+///
+/// - a signal entering the chip while travelling towards `direction` is injected in the inner pane,
+///   at the position of the first entry `(direction, x, y)` of `inputs`; it is then moved by the inner pane during the next update cycle,
+///   like a signal that reached a tile of the outer pane
+/// - a signal reaching the position `(x, y)` of an entry `(x, y, direction)` of `outputs` is taken out of the inner pane
+///   and sent out of the chip, towards `direction`
+/// - if the inner pane is [connected](Pane::connected), then a signal leaving it through one of its edges
+///   is sent out of the chip through the matching side
+///
+/// The chip only accepts signals travelling in the direction of one of its `inputs`;
+/// signals set on the chip in another direction are marked as [errored](Signal::set_error) and keep going in their direction.
+///
+/// Signals sent by the inner pane to other panes, operations on panes and writes to global variables are forwarded to the world.
+/// The [pseudo-random numbers](UpdateContext::random) drawn inside the chip depend on the position of the chip in the world.
+/// The chip stays [`Active`](State::Active) as long as its inner pane isn't [idle](Pane::is_idle),
+/// and keeps accepting signals during that time.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Chip {
+    // Boxed, to keep the size of `AnyTile` small
+    pub pane: Box<Pane>,
+    pub inputs: Vec<(Direction, usize, usize)>,
+    pub outputs: Vec<(usize, usize, Direction)>,
+}
+
+impl Chip {
+    pub fn new(
+        pane: Pane,
+        inputs: Vec<(Direction, usize, usize)>,
+        outputs: Vec<(usize, usize, Direction)>,
+    ) -> Self {
+        Self {
+            pane: Box::new(pane),
+            inputs,
+            outputs,
+        }
+    }
+}
+
+impl Default for Chip {
+    fn default() -> Self {
+        Self::new(Pane::empty(1, 1).unwrap(), Vec::new(), Vec::new())
+    }
+}
+
+impl Tile for Chip {
+    fn update<'b>(&'b mut self, mut context: UpdateContext<'b>) {
+        // Incomming signals are injected after the inner pane was stepped, so that they move during the next update,
+        // like signals sent to a tile of the outer pane
+        let incomming = context.take_signal();
+
+        if !self.pane.is_idle() {
+            // Signals leaving the inner pane through its edges leave the chip through the matching side
+            let edges: HashSet<_> = self
+                .pane
+                .edge_destinations()
+                .into_iter()
+                .filter(|&(_, direction)| {
+                    context.accepts_direction(direction).is_some()
+                        || context.accepts_edge(direction)
+                })
+                .collect();
+            let result = self.pane.step_with(context.inner_env(&edges));
+
+            for &(x, y, direction) in self.outputs.iter() {
+                let signal = self
+                    .pane
+                    .get_mut((x, y))
+                    .and_then(|tile| tile.take_signal());
+
+                if let Some(signal) = signal {
+                    let _ = context.send_direction(direction, signal);
+                }
+            }
+
+            for (_, signal) in result.edge_signals {
+                let _ = context.send_direction(signal.direction(), signal);
+            }
+
+            for (coordinates, signal) in result.outbound_signals {
+                context.send_outbound(coordinates, signal);
+            }
+
+            for operation in result.pane_operations {
+                match operation {
+                    PaneOperation::Instantiate {
+                        source,
+                        name,
+                        position,
+                    } => context.instantiate_pane(source, name, position),
+                    PaneOperation::Delete { name } => context.delete_pane(name),
+                }
+            }
+
+            for (key, value) in result.global_writes {
+                match value {
+                    Some(value) => context.set_global(key, value),
+                    None => context.remove_global(key),
+                }
+            }
+        }
+
+        if let Some(signal) = incomming {
+            let direction = signal.direction();
+
+            match self.inputs.iter().find(|(d, _, _)| *d == direction) {
+                Some(&(_, x, y)) => {
+                    let _ = self.pane.set_signal((x, y), signal);
+                }
+                None => {
+                    let mut signal = signal;
+                    signal.set_error(format!("Chip: no input towards {:?}", direction));
+                    let _ = context.send_direction(direction, signal);
+                }
+            }
+        }
+
+        if !self.pane.is_idle() {
+            context.set_state(State::Active);
+        } else if context.state() != State::Idle {
+            context.next_state();
+        }
+    }
+
+    fn accepts_signal(&self, direction: Direction) -> bool {
+        self.inputs.iter().any(|(d, _, _)| *d == direction)
+    }
+
+    fn accepts_while_active(&self) -> bool {
+        true
+    }
+
+    fn draw_simple(&self, state: State) -> TextChar {
+        TextChar::from_state('#', state)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_chip() {
+        use crate::{Diode, Wire};
+        use Direction::*;
+        use Orientation::*;
+
+        let inner = test_tile_setup!(
+            3,
+            1,
+            [
+                Wire::new(Horizontal),
+                Wire::new(Horizontal),
+                Wire::new(Horizontal)
+            ]
+        );
+
+        let mut pane = test_tile_setup!(
+            3,
+            1,
+            [
+                Diode::new(Right),
+                Chip::new(inner, vec![(Right, 0, 0)], vec![(2, 0, Right)]),
+                Wire::new(Horizontal)
+            ]
+        );
+
+        test_set_signal!(pane, (0, 0), Right);
+
+        pane.step();
+        assert_signal!(pane, (1, 0));
+
+        // The signal enters the chip, and only moves through its inner pane during the next update cycle
+        pane.step();
+        assert_no_signal!(pane, (2, 0));
+        assert_eq!(pane.get((1, 0)).unwrap().state(), State::Active);
+        {
+            let chip = pane.get_as::<Chip>((1, 0)).unwrap();
+            assert!(chip.pane.get((0, 0)).unwrap().signal().is_some());
+        }
+
+        // The chip keeps accepting signals while it is busy
+        assert!(pane.get((1, 0)).unwrap().accepts_signal(Right));
+        assert!(!pane.get((1, 0)).unwrap().accepts_signal(Left));
+
+        pane.step();
+        assert_no_signal!(pane, (2, 0));
+        {
+            let chip = pane.get_as::<Chip>((1, 0)).unwrap();
+            assert!(chip.pane.get((1, 0)).unwrap().signal().is_some());
+        }
+
+        // The signal leaves the chip after as many update cycles as it would take through three wires of the outer pane
+        pane.step();
+        assert_signal!(pane, (2, 0));
+
+        for _ in 0..4 {
+            pane.step();
+        }
+        assert_eq!(pane.get((1, 0)).unwrap().state(), State::Idle);
+        assert!(pane.get_as::<Chip>((1, 0)).unwrap().pane.is_idle());
+    }
+
+    #[test]
+    fn test_chip_edges() {
+        use crate::Wire;
+        use Direction::*;
+        use Orientation::*;
+
+        let mut inner = test_tile_setup!(2, 1, [Wire::new(Horizontal), Wire::new(Horizontal)]);
+        inner.set_connected(true);
+
+        let mut pane = test_tile_setup!(
+            3,
+            2,
+            [
+                (),
+                Chip::new(inner, vec![(Right, 0, 0)], vec![]),
+                Wire::new(Horizontal),
+                (),
+                Wire::new(Vertical),
+                (),
+            ]
+        );
+
+        // A signal leaving the inner pane through its right edge leaves the chip through its right side
+        test_set_signal!(pane, (1, 0), Right);
+        let mut received = false;
+        for _ in 0..4 {
+            pane.step();
+            received |= pane.get((2, 0)).unwrap().signal().is_some();
+        }
+        assert!(received);
+
+        // Sides without an input refuse signals; signals set in such a direction are errored and keep going
+        assert!(!pane.get((1, 0)).unwrap().accepts_signal(Down));
+        test_set_signal!(pane, (1, 0), Down);
+        pane.step();
+        assert_signal!(pane, (1, 1));
+        assert_eq!(
+            pane.get((1, 1)).unwrap().signal().unwrap().error(),
+            Some("Chip: no input towards Down")
+        );
+    }
+
+    #[test]
+    fn test_chip_random() {
+        use crate::{Random, Wire};
+        use Direction::*;
+        use Orientation::*;
+
+        let random = || Random::new(0, i64::MAX);
+
+        // The random tile of the inner pane draws during the second update cycle, at the coordinates (0, 0) of its pane
+        let inner = test_tile_setup!(2, 1, [random(), Wire::new(Horizontal)]);
+        let chip = Chip::new(inner, vec![(Right, 0, 0)], vec![(1, 0, Right)]);
+
+        let mut world = World::new();
+        world.set_pane(
+            String::from("main"),
+            test_tile_setup!(2, 1, [chip, Wire::new(Horizontal)]),
+        );
+        world
+            .get_pane_mut("main")
+            .unwrap()
+            .set_signal((0, 0), Signal::empty((0, 0), Right));
+        world.step();
+        world.step();
+        let inner_value = world.get((1, 0)).unwrap().signal().unwrap().stack()[0].clone();
+
+        // A random tile of the world drawing during the second update cycle, at the same coordinates
+        let mut world = World::new();
+        world.set_pane(
+            String::from("main"),
+            test_tile_setup!(2, 1, [random(), Wire::new(Horizontal)]),
+        );
+        world.step();
+        world
+            .get_pane_mut("main")
+            .unwrap()
+            .set_signal((0, 0), Signal::empty((0, 0), Right));
+        world.step();
+        let outer_value = world.get((1, 0)).unwrap().signal().unwrap().stack()[0].clone();
+
+        assert_ne!(inner_value, outer_value);
+    }
+}
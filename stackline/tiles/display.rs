@@ -0,0 +1,179 @@
+//! Output tiles: show the values of the signals passing through them on the drawn surface
+//!
+//! All of these tiles let the signal keep going in the same direction, without modifying it.
+
+use crate::prelude::*;
+use crate::tile::prelude::*;
+use palette::Srgb;
+
+/// Remembers the value on top of the stack of the last signal that went through it,
+/// and draws the text of that value (see the `Display` implementation of [`Value`]) to the right of itself.
+///
+/// At most `width` characters are drawn; the text is drawn over the tiles to its right that are drawn before the display,
+/// and is hidden by the non-empty tiles drawn after it.
+/// If the last signal had an empty stack, then nothing is drawn besides the tile itself.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Display {
+    pub width: usize,
+    pub value: Option<Value>,
+}
+
+impl Display {
+    pub fn new(width: usize) -> Self {
+        Self { width, value: None }
+    }
+}
+
+impl Default for Display {
+    fn default() -> Self {
+        Self::new(8)
+    }
+}
+
+impl Tile for Display {
+    fn update<'b>(&'b mut self, mut context: UpdateContext<'b>) {
+        if let Some(signal) = context.take_signal() {
+            self.value = signal.stack().last().cloned();
+
+            let _ = context.send_direction(signal.direction(), signal);
+        }
+
+        if context.state() != State::Idle {
+            context.next_state();
+        }
+    }
+
+    fn draw(&self, x: i32, y: i32, state: State, surface: &mut TextSurface) {
+        let (x, y) = match (usize::try_from(x), usize::try_from(y)) {
+            (Ok(x), Ok(y)) => (x, y),
+            _ => return,
+        };
+
+        surface.set(x, y, self.draw_simple(state));
+
+        if let Some(value) = &self.value {
+            for (dx, ch) in value.to_string().chars().take(self.width).enumerate() {
+                surface.set(x + dx + 1, y, TextChar::from_char(ch));
+            }
+        }
+    }
+
+    fn draw_simple(&self, state: State) -> TextChar {
+        TextChar::from_state('D', state)
+    }
+}
+
+/// Lights up in `color` if the value on top of the stack of the last signal that went through it
+/// [is truthy](Value::is_truthy), and goes dark otherwise.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Lamp {
+    /// The color of the lit lamp, as red, green and blue components
+    pub color: (u8, u8, u8),
+    pub lit: bool,
+}
+
+impl Lamp {
+    pub fn new(color: (u8, u8, u8)) -> Self {
+        Self { color, lit: false }
+    }
+}
+
+impl Default for Lamp {
+    fn default() -> Self {
+        Self::new((255, 200, 0))
+    }
+}
+
+impl Tile for Lamp {
+    fn update<'b>(&'b mut self, mut context: UpdateContext<'b>) {
+        if let Some(signal) = context.take_signal() {
+            self.lit = signal.stack().last().is_some_and(Value::is_truthy);
+
+            let _ = context.send_direction(signal.direction(), signal);
+        }
+
+        if context.state() != State::Idle {
+            context.next_state();
+        }
+    }
+
+    fn draw_simple(&self, state: State) -> TextChar {
+        if self.lit {
+            let (red, green, blue) = self.color;
+            TextChar {
+                ch: '\u{25c9}', // Fisheye
+                fg: Srgb::new(red, green, blue),
+                bg: None,
+            }
+        } else {
+            TextChar::from_state('\u{25cc}', state) // Dotted Circle
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Wire;
+    use Direction::*;
+    use Orientation::*;
+
+    fn draw(pane: &Pane) -> String {
+        let mut surface = TextSurface::new(pane.width().get(), pane.height().get());
+        pane.draw(0, 0, &mut surface);
+
+        (0..surface.width())
+            .map(|x| surface.get(x, 0).unwrap().ch)
+            .collect()
+    }
+
+    #[test]
+    fn test_display() {
+        let mut pane = test_tile_setup!(
+            7,
+            1,
+            [Display::new(4), (), (), (), (), (), Wire::new(Horizontal)]
+        );
+
+        assert_eq!(draw(&pane), "D     -");
+
+        pane.set_signal((0, 0), crate::signal!((0, 0), Right, [1, 2.5]));
+        pane.step();
+        assert_eq!(
+            pane.get_as::<Display>((0, 0)).unwrap().value,
+            Some(Value::Number(2.5))
+        );
+        assert_eq!(draw(&pane), "D2.5  -");
+
+        pane.step();
+        pane.step();
+
+        // The text is cut after `width` characters, and is hidden by the tiles to its right
+        pane.set_signal((0, 0), crate::signal!((0, 0), Right, ["abcdefgh"]));
+        pane.step();
+        assert_eq!(draw(&pane), "Dabcd -");
+        pane.get_mut_as::<Display>((0, 0)).unwrap().width = 10;
+        assert_eq!(draw(&pane), "Dabcde-");
+    }
+
+    #[test]
+    fn test_lamp() {
+        let mut pane = test_tile_setup!(2, 1, [Lamp::new((255, 0, 0)), Wire::new(Horizontal)]);
+
+        for (value, lit) in [(1, true), (0, false)] {
+            pane.set_signal((0, 0), crate::signal!((0, 0), Right, [value]));
+            pane.step();
+            assert_signal!(pane, (1, 0));
+            assert_eq!(pane.get_as::<Lamp>((0, 0)).unwrap().lit, lit);
+
+            pane.step();
+            pane.step();
+        }
+
+        let lamp = Lamp {
+            color: (255, 0, 0),
+            lit: true,
+        };
+        assert_eq!(lamp.draw_simple(State::Idle).fg, Srgb::new(255, 0, 0));
+    }
+}
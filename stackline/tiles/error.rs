@@ -0,0 +1,95 @@
+//! Error handling tiles: route the signals on which an operation failed
+
+use crate::prelude::*;
+use crate::tile::prelude::*;
+
+/// Sends [errored](Signal::set_error) signals towards `direction`, after clearing their error and pushing its message onto their stack.
+/// Other signals keep going in the same direction.
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+pub struct Catch {
+    pub direction: Direction,
+}
+
+impl Catch {
+    pub fn new(direction: Direction) -> Self {
+        Self { direction }
+    }
+}
+
+impl Tile for Catch {
+    fn update<'b>(&'b mut self, mut context: UpdateContext<'b>) {
+        if let Some(mut signal) = context.take_signal() {
+            match signal.take_error() {
+                Some(message) => {
+                    signal.push(Value::String(message));
+                    let _ = context.send_direction(self.direction, signal.moved(self.direction));
+                }
+                None => {
+                    let _ = context.send_direction(signal.direction(), signal);
+                }
+            }
+        }
+
+        if context.state() != State::Idle {
+            context.next_state();
+        }
+    }
+
+    fn draw_simple(&self, state: State) -> TextChar {
+        TextChar::from_state('?', state)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_catch() {
+        use crate::{Diode, Pack, Wire};
+        use Direction::*;
+        use Orientation::*;
+
+        let mut pane = test_tile_setup!(
+            4,
+            2,
+            [
+                Diode::new(Right),
+                Pack::new(1),
+                Catch::new(Down),
+                Wire::new(Horizontal),
+                (),
+                (),
+                Wire::new(Vertical),
+                (),
+            ]
+        );
+
+        // Packing one value succeeds, so the signal isn't caught
+        pane.set_signal((0, 0), crate::signal!((0, 0), Right, [1]));
+        for _ in 0..3 {
+            pane.step();
+        }
+        assert_signal!(pane, (3, 0));
+        assert_no_signal!(pane, (2, 1));
+
+        for _ in 0..3 {
+            pane.step();
+        }
+
+        // Packing one value out of an empty stack fails, so the signal is caught
+        test_set_signal!(pane, (0, 0), Right);
+        for _ in 0..3 {
+            pane.step();
+        }
+        assert_no_signal!(pane, (3, 0));
+        assert_signal!(pane, (2, 1));
+
+        let signal = pane.get((2, 1)).unwrap().signal().cloned().unwrap();
+        assert!(!signal.is_errored());
+        assert_eq!(
+            signal.stack(),
+            &vec![Value::from("Pack: expected 1 values, found 0")]
+        );
+    }
+}
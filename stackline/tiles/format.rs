@@ -0,0 +1,235 @@
+//! Formatting tiles: turn values into text
+
+use crate::prelude::*;
+use crate::tile::prelude::*;
+
+/// Largest precision accepted in the placeholders of a [`Format::template`]
+const MAX_PRECISION: usize = 64;
+
+/// A part of a parsed [`Format::template`]
+#[derive(Clone, Debug, PartialEq)]
+enum Segment<'a> {
+    Text(&'a str),
+    /// A placeholder, with its optional precision
+    Placeholder(Option<usize>),
+}
+
+/// Splits `template` into text and placeholders, returning an error message if the template is invalid.
+fn parse_template(template: &str) -> Result<Vec<Segment<'_>>, String> {
+    let mut segments = Vec::new();
+    let mut rest = template;
+
+    while let Some(index) = rest.find(['{', '}']) {
+        if index > 0 {
+            segments.push(Segment::Text(&rest[..index]));
+        }
+        rest = &rest[index..];
+
+        if rest.starts_with("{{") {
+            segments.push(Segment::Text("{"));
+            rest = &rest[2..];
+        } else if rest.starts_with("}}") {
+            segments.push(Segment::Text("}"));
+            rest = &rest[2..];
+        } else if rest.starts_with('}') {
+            return Err(String::from("Format: unmatched '}' in template"));
+        } else {
+            let end = rest
+                .find('}')
+                .ok_or_else(|| String::from("Format: unmatched '{' in template"))?;
+
+            let precision = match &rest[1..end] {
+                "" => None,
+                spec => match spec.strip_prefix(":.").map(str::parse::<usize>) {
+                    Some(Ok(precision)) if precision > MAX_PRECISION => {
+                        return Err(format!(
+                            "Format: precision {} is greater than {}",
+                            precision, MAX_PRECISION
+                        ))
+                    }
+                    Some(Ok(precision)) => Some(precision),
+                    _ => return Err(format!("Format: invalid placeholder '{{{}}}'", spec)),
+                },
+            };
+            segments.push(Segment::Placeholder(precision));
+            rest = &rest[end + 1..];
+        }
+    }
+
+    if !rest.is_empty() {
+        segments.push(Segment::Text(rest));
+    }
+
+    Ok(segments)
+}
+
+/// Pops one value for each placeholder of `template` and pushes the resulting text, as a [`String`](Value::String).
+/// The signal then keeps going in the same direction.
+///
+/// The placeholders are filled with the text of the values (see the `Display` implementation of [`Value`]), in the order in which they were pushed:
+/// - `{}` is replaced with the value
+/// - `{:.N}` is replaced with the value, with numbers being written with `N` digits after the decimal point;
+///   `N` may be at most 64
+/// - `{{` and `}}` are replaced with `{` and `}`
+///
+/// If the template is invalid or if there aren't enough values on the stack, then the stack is left untouched
+/// and the signal is marked as [errored](Signal::set_error).
+///
+/// ## Example
+///
+/// A template of `"x={} y={:.1}"` turns a stack of `[1, 2.26]` into `["x=1 y=2.3"]`.
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+pub struct Format {
+    pub template: String,
+}
+
+impl Format {
+    pub fn new(template: impl Into<String>) -> Self {
+        Self {
+            template: template.into(),
+        }
+    }
+
+    /// Formats the values at the end of `stack`, returning the text and the number of values used
+    fn format(&self, stack: &[Value]) -> Result<(String, usize), String> {
+        let segments = parse_template(&self.template)?;
+        let count = segments
+            .iter()
+            .filter(|segment| matches!(segment, Segment::Placeholder(_)))
+            .count();
+
+        if stack.len() < count {
+            return Err(format!(
+                "Format: expected {} values, found {}",
+                count,
+                stack.len()
+            ));
+        }
+
+        let mut values = stack[stack.len() - count..].iter();
+        let mut text = String::new();
+        for segment in segments {
+            match segment {
+                Segment::Text(part) => text.push_str(part),
+                Segment::Placeholder(None) => text += &values.next().unwrap().to_string(),
+                Segment::Placeholder(Some(precision)) => {
+                    text += &format!("{:.*}", precision, values.next().unwrap())
+                }
+            }
+        }
+
+        Ok((text, count))
+    }
+}
+
+impl Tile for Format {
+    fn update<'b>(&'b mut self, mut context: UpdateContext<'b>) {
+        if let Some(mut signal) = context.take_signal() {
+            match self.format(signal.stack()) {
+                Ok((text, count)) => {
+                    let stack = signal.stack_mut();
+                    stack.truncate(stack.len() - count);
+                    stack.push(Value::String(text));
+                }
+                Err(message) => signal.set_error(message),
+            }
+
+            let _ = context.send_direction(signal.direction(), signal);
+        }
+
+        if context.state() != State::Idle {
+            context.next_state();
+        }
+    }
+
+    fn draw_simple(&self, state: State) -> TextChar {
+        TextChar::from_state('F', state)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_template() {
+        use Segment::*;
+
+        assert_eq!(
+            parse_template("x={} y={:.2}!"),
+            Ok(vec![
+                Text("x="),
+                Placeholder(None),
+                Text(" y="),
+                Placeholder(Some(2)),
+                Text("!")
+            ])
+        );
+        assert_eq!(
+            parse_template("{{}}{}"),
+            Ok(vec![Text("{"), Text("}"), Placeholder(None)])
+        );
+        assert_eq!(parse_template(""), Ok(vec![]));
+
+        assert!(parse_template("{").is_err());
+        assert!(parse_template("}").is_err());
+        assert!(parse_template("{:x}").is_err());
+        assert!(parse_template("{:.}").is_err());
+        assert_eq!(
+            parse_template("{:.64}"),
+            Ok(vec![Placeholder(Some(MAX_PRECISION))])
+        );
+        assert_eq!(
+            parse_template("{:.999999999}"),
+            Err(String::from(
+                "Format: precision 999999999 is greater than 64"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_format() {
+        use crate::Wire;
+        use Direction::*;
+        use Orientation::*;
+
+        let mut pane = test_tile_setup!(
+            2,
+            1,
+            [Format::new("x={} y={:.1} {}"), Wire::new(Horizontal)]
+        );
+
+        pane.set_signal((0, 0), crate::signal!((0, 0), Right, [0, 1, 2.26, "a"]));
+        pane.step();
+        assert_signal!(pane, (1, 0));
+        let signal = pane.get((1, 0)).unwrap().signal().cloned().unwrap();
+        assert_eq!(
+            signal.stack(),
+            &vec![Value::Int(0), Value::from("x=1 y=2.3 a")]
+        );
+
+        pane.step();
+        pane.step();
+
+        // Not enough values: the stack is left untouched
+        pane.set_signal((0, 0), crate::signal!((0, 0), Right, [1, 2]));
+        pane.step();
+        let signal = pane.get((1, 0)).unwrap().signal().cloned().unwrap();
+        assert_eq!(signal.error(), Some("Format: expected 3 values, found 2"));
+        assert_eq!(signal.stack(), &vec![Value::Int(1), Value::Int(2)]);
+
+        pane.step();
+        pane.step();
+
+        // Precisions above the cap are refused: the stack is left untouched
+        pane.get_mut_as::<Format>((0, 0)).unwrap().template = String::from("{:.999999999}");
+        pane.set_signal((0, 0), crate::signal!((0, 0), Right, [1]));
+        pane.step();
+        let signal = pane.get((1, 0)).unwrap().signal().cloned().unwrap();
+        assert_eq!(
+            signal.error(),
+            Some("Format: precision 999999999 is greater than 64")
+        );
+        assert_eq!(signal.stack(), &vec![Value::Int(1)]);
+    }
+}
@@ -0,0 +1,320 @@
+//! Control flow tiles: let signals through or route them depending on their values or on a stored state
+
+use crate::prelude::*;
+use crate::tile::prelude::*;
+
+/// Lets signals travelling along `orientation` through while it is open, and refuses them while it is closed.
+///
+/// Signals entering the gate from the other sides are control signals, which are consumed by the gate:
+/// - if the top of their stack is a [`Bool`](Value::Bool), then the gate is opened if it is `true` and closed if it is `false`
+/// - otherwise, the gate is toggled
+///
+/// A gate with an orientation of [`Any`](Orientation::Any) has no control sides.
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+pub struct Gate {
+    pub orientation: Orientation,
+    pub open: bool,
+}
+
+impl Gate {
+    pub fn new(orientation: Orientation, open: bool) -> Self {
+        Self { orientation, open }
+    }
+}
+
+impl Tile for Gate {
+    fn update<'b>(&'b mut self, mut context: UpdateContext<'b>) {
+        if let Some(signal) = context.take_signal() {
+            let direction = signal.direction();
+
+            if self.orientation.contains(direction) {
+                if self.open {
+                    let _ = context.send_direction(direction, signal);
+                }
+            } else {
+                self.open = match signal.stack().last() {
+                    Some(Value::Bool(open)) => *open,
+                    _ => !self.open,
+                };
+            }
+        }
+
+        if context.state() != State::Idle {
+            context.next_state();
+        }
+    }
+
+    fn accepts_signal(&self, direction: Direction) -> bool {
+        self.open || !self.orientation.contains(direction)
+    }
+
+    fn draw_simple(&self, state: State) -> TextChar {
+        let ch = if self.open {
+            '\u{25cb}' // White Circle
+        } else {
+            '\u{25cf}' // Black Circle
+        };
+
+        TextChar::from_state(ch, state)
+    }
+}
+
+/// Pops an integer from the stack of incomming signals and sends them towards the matching direction:
+/// `0` is [`Up`](Direction::Up), `1` is [`Right`](Direction::Right), `2` is [`Down`](Direction::Down) and `3` is [`Left`](Direction::Left).
+///
+/// If the stack doesn't hold such an integer, then the signal is marked as [errored](Signal::set_error)
+/// and keeps going in the same direction.
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+pub struct Demux;
+
+impl Demux {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Tile for Demux {
+    fn update<'b>(&'b mut self, mut context: UpdateContext<'b>) {
+        if let Some(mut signal) = context.take_signal() {
+            let direction = match signal.stack().last().and_then(Value::as_int) {
+                Some(0) => Ok(Direction::Up),
+                Some(1) => Ok(Direction::Right),
+                Some(2) => Ok(Direction::Down),
+                Some(3) => Ok(Direction::Left),
+                Some(n) => Err(format!(
+                    "Demux: expected an integer between 0 and 3, got {}",
+                    n
+                )),
+                None => Err(String::from("Demux: expected an integer")),
+            };
+
+            match direction {
+                Ok(direction) => {
+                    signal.pop();
+                    let _ = context.send_direction(direction, signal.moved(direction));
+                }
+                Err(message) => {
+                    signal.set_error(message);
+                    let _ = context.send_direction(signal.direction(), signal);
+                }
+            }
+        }
+
+        if context.state() != State::Idle {
+            context.next_state();
+        }
+    }
+
+    fn draw_simple(&self, state: State) -> TextChar {
+        TextChar::from_state('Y', state)
+    }
+}
+
+/// Sends incomming signals to each of its `outputs` in turn, skipping the outputs whose neighbor doesn't accept the signal.
+/// Outputs crossing the edge of a [connected](Pane::connected) pane are skipped unless the tile behind that edge accepts the signal.
+/// `next` is the index in `outputs` of the next output to try.
+///
+/// If none of the outputs accept the signal, then the signal is dropped.
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+pub struct Sequencer {
+    pub outputs: Vec<Direction>,
+    pub next: usize,
+}
+
+impl Sequencer {
+    pub fn new(outputs: Vec<Direction>) -> Self {
+        Self { outputs, next: 0 }
+    }
+}
+
+impl Tile for Sequencer {
+    fn update<'b>(&'b mut self, mut context: UpdateContext<'b>) {
+        if let Some(signal) = context.take_signal() {
+            let length = self.outputs.len();
+
+            for n in 0..length {
+                let index = (self.next + n) % length;
+                let direction = self.outputs[index];
+
+                if context.accepts_direction(direction).is_some() || context.accepts_edge(direction)
+                {
+                    let _ = context.send_direction(direction, signal.moved(direction));
+                    self.next = (index + 1) % length;
+                    break;
+                }
+            }
+        }
+
+        if context.state() != State::Idle {
+            context.next_state();
+        }
+    }
+
+    fn draw_simple(&self, state: State) -> TextChar {
+        TextChar::from_state('%', state)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_gate() {
+        use crate::{Diode, Wire};
+        use Direction::*;
+        use Orientation::*;
+
+        let mut pane = test_tile_setup!(
+            3,
+            1,
+            [
+                Diode::new(Right),
+                Gate::new(Horizontal, false),
+                Wire::new(Horizontal)
+            ]
+        );
+
+        // Closed: the signal is refused
+        test_set_signal!(pane, (0, 0), Right);
+        pane.step();
+        assert_no_signal!(pane, (1, 0));
+        assert!(!pane.get((1, 0)).unwrap().accepts_signal(Right));
+        assert!(pane.get((1, 0)).unwrap().accepts_signal(Down));
+
+        // A control signal without a boolean toggles the gate
+        test_set_signal!(pane, (1, 0), Down);
+        pane.step();
+        assert!(pane.get_as::<Gate>((1, 0)).unwrap().open);
+        assert_no_signal!(pane, (1, 0));
+        pane.step();
+
+        // Open: the signal goes through
+        test_set_signal!(pane, (0, 0), Right);
+        pane.step();
+        assert_signal!(pane, (1, 0));
+        pane.step();
+        assert_signal!(pane, (2, 0));
+        pane.step();
+
+        // A control signal with a boolean sets the state of the gate
+        for open in [true, false, false] {
+            pane.set_signal((1, 0), crate::signal!((1, 0), Up, [open]));
+            pane.step();
+            pane.step();
+            assert_eq!(pane.get_as::<Gate>((1, 0)).unwrap().open, open);
+        }
+    }
+
+    #[test]
+    fn test_demux() {
+        use crate::Wire;
+        use Direction::*;
+        use Orientation::*;
+
+        let setup = || {
+            test_tile_setup!(
+                3,
+                3,
+                [
+                    (),
+                    Wire::new(Any),
+                    (),
+                    Wire::new(Any),
+                    Demux::new(),
+                    Wire::new(Any),
+                    (),
+                    Wire::new(Any),
+                    (),
+                ]
+            )
+        };
+
+        for (n, position) in [(0, (1, 0)), (1, (2, 1)), (2, (1, 2)), (3, (0, 1))] {
+            let mut pane = setup();
+            pane.set_signal((1, 1), crate::signal!((1, 1), Left, [7, n]));
+            pane.step();
+
+            assert_signal!(pane, position);
+            let signal = pane.get(position).unwrap().signal().cloned().unwrap();
+            assert_eq!(signal.stack(), &vec![Value::Int(7)]);
+            assert!(!signal.is_errored());
+        }
+
+        // Invalid indices mark the signal as errored
+        let mut pane = setup();
+        pane.set_signal((1, 1), crate::signal!((1, 1), Left, [4]));
+        pane.step();
+        assert!(pane.get((0, 1)).unwrap().signal().unwrap().is_errored());
+    }
+
+    #[test]
+    fn test_sequencer() {
+        use crate::{Diode, Wire};
+        use Direction::*;
+        use Orientation::*;
+
+        let mut pane = test_tile_setup!(
+            3,
+            2,
+            [
+                Diode::new(Right),
+                Sequencer::new(vec![Right, Down, Left]),
+                Wire::new(Horizontal),
+                (),
+                Wire::new(Vertical),
+                (),
+            ]
+        );
+
+        // The left output never accepts signals, since the diode refuses signals coming from the tile it points to
+        let mut outputs = Vec::new();
+        for _ in 0..4 {
+            test_set_signal!(pane, (1, 0), Right);
+            pane.step();
+
+            if pane.get((2, 0)).unwrap().signal().is_some() {
+                outputs.push(Right);
+            }
+            if pane.get((1, 1)).unwrap().signal().is_some() {
+                outputs.push(Down);
+            }
+
+            // Let the wires go back to idle
+            pane.step();
+            pane.step();
+        }
+
+        assert_eq!(outputs, vec![Right, Down, Right, Down]);
+    }
+
+    #[test]
+    fn test_sequencer_edge() {
+        use crate::Wire;
+        use Direction::*;
+        use Orientation::*;
+
+        let mut main = test_tile_setup!(1, 1, [Sequencer::new(vec![Up, Right])]);
+        main.set_connected(true);
+
+        let mut right = test_tile_setup!(1, 1, [Wire::new(Horizontal)]);
+        right.set_position((1, 0));
+
+        let mut world = World::new();
+        world.set_pane(String::from("main"), main);
+        world.set_pane(String::from("right"), right);
+
+        // There is no pane above: every signal goes through the right edge
+        for _ in 0..2 {
+            world
+                .get_pane_mut("main")
+                .unwrap()
+                .set_signal((0, 0), Signal::empty((0, 0), Right));
+            world.step();
+            assert!(world.get((1, 0)).unwrap().signal().is_some());
+
+            world.step();
+            world.step();
+        }
+    }
+}
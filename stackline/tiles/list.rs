@@ -0,0 +1,350 @@
+//! List tiles: pack values into lists, unpack them and access their elements
+//!
+//! All of these tiles let the signal keep going in the same direction.
+//! If the stack of the signal doesn't contain the expected values, then it is left untouched
+//! and the signal is marked as [errored](Signal::set_error).
+
+use crate::prelude::*;
+use crate::tile::prelude::*;
+
+/// Pops the top `count` values of the stack and pushes them back as a single [`List`](Value::List).
+/// The bottom-most of these values becomes the first element of the list.
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+pub struct Pack {
+    pub count: usize,
+}
+
+impl Pack {
+    pub fn new(count: usize) -> Self {
+        Self { count }
+    }
+}
+
+impl Tile for Pack {
+    fn update<'b>(&'b mut self, mut context: UpdateContext<'b>) {
+        if let Some(mut signal) = context.take_signal() {
+            let stack = signal.stack_mut();
+
+            if stack.len() >= self.count {
+                let list = stack.split_off(stack.len() - self.count);
+                stack.push(Value::List(list));
+            } else {
+                let message = format!(
+                    "Pack: expected {} values, found {}",
+                    self.count,
+                    stack.len()
+                );
+                signal.set_error(message);
+            }
+
+            let _ = context.send_direction(signal.direction(), signal);
+        }
+
+        if context.state() != State::Idle {
+            context.next_state();
+        }
+    }
+
+    fn draw_simple(&self, state: State) -> TextChar {
+        TextChar::from_state('[', state)
+    }
+}
+
+/// Pops a [`List`](Value::List) from the stack and pushes its elements, starting with the first one.
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+pub struct Unpack;
+
+impl Unpack {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Tile for Unpack {
+    fn update<'b>(&'b mut self, mut context: UpdateContext<'b>) {
+        if let Some(mut signal) = context.take_signal() {
+            let stack = signal.stack_mut();
+
+            if let Some(Value::List(_)) = stack.last() {
+                if let Some(Value::List(mut list)) = stack.pop() {
+                    stack.append(&mut list);
+                }
+            } else {
+                signal.set_error("Unpack: expected a list");
+            }
+
+            let _ = context.send_direction(signal.direction(), signal);
+        }
+
+        if context.state() != State::Idle {
+            context.next_state();
+        }
+    }
+
+    fn draw_simple(&self, state: State) -> TextChar {
+        TextChar::from_state(']', state)
+    }
+}
+
+/// Pops an index from the stack, then pushes a copy of the element at that index of the [`List`](Value::List)
+/// found below it. The list is kept on the stack.
+///
+/// Negative indices count from the end of the list, with `-1` being the last element.
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+pub struct Index;
+
+impl Index {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Tile for Index {
+    fn update<'b>(&'b mut self, mut context: UpdateContext<'b>) {
+        if let Some(mut signal) = context.take_signal() {
+            let stack = signal.stack_mut();
+
+            let element = match stack.as_slice() {
+                [.., Value::List(list), index] => match index.as_int() {
+                    Some(index) => {
+                        let actual_index = if index < 0 {
+                            list.len() as i64 + index
+                        } else {
+                            index
+                        };
+
+                        usize::try_from(actual_index)
+                            .ok()
+                            .and_then(|index| list.get(index))
+                            .cloned()
+                            .ok_or_else(|| format!("Index: index {} is out of bounds", index))
+                    }
+                    None => Err(String::from("Index: expected a number as index")),
+                },
+                _ => Err(String::from("Index: expected a list and an index")),
+            };
+
+            match element {
+                Ok(element) => {
+                    stack.pop();
+                    stack.push(element);
+                }
+                Err(message) => signal.set_error(message),
+            }
+
+            let _ = context.send_direction(signal.direction(), signal);
+        }
+
+        if context.state() != State::Idle {
+            context.next_state();
+        }
+    }
+
+    fn draw_simple(&self, state: State) -> TextChar {
+        TextChar::from_state('i', state)
+    }
+}
+
+/// Pops a value from the stack and appends it to the [`List`](Value::List) found below it.
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+pub struct Append;
+
+impl Append {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Tile for Append {
+    fn update<'b>(&'b mut self, mut context: UpdateContext<'b>) {
+        if let Some(mut signal) = context.take_signal() {
+            let stack = signal.stack_mut();
+
+            if let [.., Value::List(_), _] = stack.as_slice() {
+                let value = stack.pop().unwrap();
+                if let Some(Value::List(list)) = stack.last_mut() {
+                    list.push(value);
+                }
+            } else {
+                signal.set_error("Append: expected a list and a value");
+            }
+
+            let _ = context.send_direction(signal.direction(), signal);
+        }
+
+        if context.state() != State::Idle {
+            context.next_state();
+        }
+    }
+
+    fn draw_simple(&self, state: State) -> TextChar {
+        TextChar::from_state('a', state)
+    }
+}
+
+/// Pushes the length of the [`List`](Value::List) on top of the stack, as an [`Int`](Value::Int).
+/// The list is kept on the stack.
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+pub struct Length;
+
+impl Length {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Tile for Length {
+    fn update<'b>(&'b mut self, mut context: UpdateContext<'b>) {
+        if let Some(mut signal) = context.take_signal() {
+            if let Some(Value::List(list)) = signal.stack().last() {
+                let length = list.len() as i64;
+                signal.push(Value::Int(length));
+            } else {
+                signal.set_error("Length: expected a list");
+            }
+
+            let _ = context.send_direction(signal.direction(), signal);
+        }
+
+        if context.state() != State::Idle {
+            context.next_state();
+        }
+    }
+
+    fn draw_simple(&self, state: State) -> TextChar {
+        TextChar::from_state('l', state)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Wire;
+    use Direction::*;
+    use Orientation::*;
+
+    /// Sends a signal holding `stack` through `tile`, and returns the signal once it went through
+    fn run_signal(tile: impl Into<FullTile>, stack: Vec<Value>) -> Signal {
+        let tile: FullTile = tile.into();
+        let mut pane = test_tile_setup!(2, 1, [tile, Wire::new(Horizontal)]);
+
+        let mut signal = Signal::empty((0, 0), Right);
+        *signal.stack_mut() = stack;
+        pane.set_signal((0, 0), signal);
+
+        pane.step();
+        assert_signal!(pane, (1, 0));
+
+        let signal = pane.get((1, 0)).unwrap().signal().cloned().unwrap();
+        signal
+    }
+
+    /// Returns the stack of the signal once it went through `tile`, checking that the operation succeeded
+    fn run_tile(tile: impl Into<FullTile>, stack: Vec<Value>) -> Vec<Value> {
+        let signal = run_signal(tile, stack);
+        assert_eq!(signal.error(), None);
+
+        signal.stack().clone()
+    }
+
+    /// Returns the stack of the signal once it went through `tile`, checking that the operation failed
+    fn run_tile_errored(tile: impl Into<FullTile>, stack: Vec<Value>) -> Vec<Value> {
+        let signal = run_signal(tile, stack);
+        assert!(signal.is_errored());
+
+        signal.stack().clone()
+    }
+
+    fn list(values: Vec<Value>) -> Value {
+        Value::List(values)
+    }
+
+    #[test]
+    fn test_pack() {
+        let stack = vec![Value::Int(1), Value::Int(2), Value::Int(3)];
+
+        assert_eq!(
+            run_tile(Pack::new(2), stack.clone()),
+            vec![Value::Int(1), list(vec![Value::Int(2), Value::Int(3)])]
+        );
+        assert_eq!(run_tile(Pack::new(0), vec![]), vec![list(vec![])]);
+
+        // Not enough values
+        assert_eq!(run_tile_errored(Pack::new(4), stack.clone()), stack);
+    }
+
+    #[test]
+    fn test_unpack() {
+        let packed = list(vec![Value::Int(2), Value::from("a")]);
+
+        assert_eq!(
+            run_tile(Unpack::new(), vec![Value::Int(1), packed]),
+            vec![Value::Int(1), Value::Int(2), Value::from("a")]
+        );
+        assert_eq!(
+            run_tile_errored(Unpack::new(), vec![Value::Int(1)]),
+            vec![Value::Int(1)]
+        );
+
+        // Unpacking a packed stack gives back the original stack
+        let stack = vec![Value::Int(1), Value::Int(2), Value::Int(3)];
+        let packed = run_tile(Pack::new(3), stack.clone());
+        assert_eq!(run_tile(Unpack::new(), packed), stack);
+    }
+
+    #[test]
+    fn test_index() {
+        let packed = list(vec![Value::Int(10), Value::Int(20), Value::Int(30)]);
+
+        assert_eq!(
+            run_tile(Index::new(), vec![packed.clone(), Value::Int(1)]),
+            vec![packed.clone(), Value::Int(20)]
+        );
+        assert_eq!(
+            run_tile(Index::new(), vec![packed.clone(), Value::Int(-1)]),
+            vec![packed.clone(), Value::Int(30)]
+        );
+
+        // Out of bounds
+        assert_eq!(
+            run_tile_errored(Index::new(), vec![packed.clone(), Value::Int(3)]),
+            vec![packed.clone(), Value::Int(3)]
+        );
+        assert_eq!(
+            run_tile_errored(Index::new(), vec![packed.clone(), Value::Int(-4)]),
+            vec![packed.clone(), Value::Int(-4)]
+        );
+
+        // Type mismatch
+        assert_eq!(
+            run_signal(Index::new(), vec![packed.clone(), Value::from("a")]).error(),
+            Some("Index: expected a number as index")
+        );
+    }
+
+    #[test]
+    fn test_append() {
+        assert_eq!(
+            run_tile(
+                Append::new(),
+                vec![list(vec![Value::Int(1)]), Value::Int(2)]
+            ),
+            vec![list(vec![Value::Int(1), Value::Int(2)])]
+        );
+        assert_eq!(
+            run_tile_errored(Append::new(), vec![Value::Int(1), Value::Int(2)]),
+            vec![Value::Int(1), Value::Int(2)]
+        );
+    }
+
+    #[test]
+    fn test_length() {
+        let packed = list(vec![Value::Int(1), Value::Nil]);
+
+        assert_eq!(
+            run_tile(Length::new(), vec![packed.clone()]),
+            vec![packed, Value::Int(2)]
+        );
+        assert_eq!(run_tile_errored(Length::new(), vec![]), vec![]);
+    }
+}
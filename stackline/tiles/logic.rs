@@ -0,0 +1,306 @@
+//! Logic tiles: bitwise operations on integers, and logical operations on the truthiness of values
+//!
+//! Unary operations pop one value `x`, binary operations pop `b`, then `a` (so `a` is the value that was pushed first).
+//! The result is pushed in their place and the signal keeps going in the same direction.
+//! If the operation fails, then the stack is left untouched and the signal is marked as [errored](Signal::set_error).
+
+use crate::prelude::*;
+use crate::tile::prelude::*;
+
+/// The operations that a [`Bitwise`] tile can apply.
+/// Their arguments must be integers, as given by [`Value::as_int`], and their result is an [`Int`](Value::Int).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum BitwiseOperation {
+    /// Bitwise AND of `a` and `b`
+    #[default]
+    And,
+    /// Bitwise OR of `a` and `b`
+    Or,
+    /// Bitwise XOR of `a` and `b`
+    Xor,
+    /// Bitwise NOT of `x`
+    Not,
+    /// `a` shifted `b` bits to the left; bits shifted past the most significant bit are lost
+    ShiftLeft,
+    /// `a` shifted `b` bits to the right, keeping its sign
+    ShiftRight,
+}
+
+impl BitwiseOperation {
+    /// Returns the number of values that the operation pops from the stack
+    pub fn arity(self) -> usize {
+        match self {
+            BitwiseOperation::Not => 1,
+            _ => 2,
+        }
+    }
+
+    /// Returns the character used to draw a [`Bitwise`] tile applying this operation
+    pub fn mnemonic(self) -> char {
+        match self {
+            BitwiseOperation::And => '\u{2229}',        // Intersection
+            BitwiseOperation::Or => '\u{222a}',         // Union
+            BitwiseOperation::Xor => '\u{2295}',        // Circled Plus
+            BitwiseOperation::Not => '\u{2201}',        // Complement
+            BitwiseOperation::ShiftLeft => '\u{00ab}',  // Left-Pointing Double Angle Quotation Mark
+            BitwiseOperation::ShiftRight => '\u{00bb}', // Right-Pointing Double Angle Quotation Mark
+        }
+    }
+
+    /// Applies the operation to `args`, which must contain [`arity`](BitwiseOperation::arity) values.
+    /// Returns an error message if the arguments aren't numbers, or if the shift amount isn't between 0 and 63.
+    pub fn apply(self, args: &[Value]) -> Result<Value, String> {
+        if args.len() != self.arity() {
+            return Err(format!(
+                "{:?}: expected {} values, found {}",
+                self,
+                self.arity(),
+                args.len()
+            ));
+        }
+
+        let ints = args
+            .iter()
+            .map(Value::as_int)
+            .collect::<Option<Vec<i64>>>()
+            .ok_or_else(|| format!("{:?}: expected integers", self))?;
+
+        let shift = |amount: i64| {
+            u32::try_from(amount)
+                .ok()
+                .filter(|amount| *amount < i64::BITS)
+                .ok_or_else(|| format!("{:?}: invalid shift amount {}", self, amount))
+        };
+
+        let result = match (self, ints.as_slice()) {
+            (BitwiseOperation::And, [a, b]) => a & b,
+            (BitwiseOperation::Or, [a, b]) => a | b,
+            (BitwiseOperation::Xor, [a, b]) => a ^ b,
+            (BitwiseOperation::Not, [x]) => !x,
+            (BitwiseOperation::ShiftLeft, [a, b]) => a << shift(*b)?,
+            (BitwiseOperation::ShiftRight, [a, b]) => a >> shift(*b)?,
+            _ => unreachable!(),
+        };
+
+        Ok(Value::Int(result))
+    }
+}
+
+/// Applies a [bitwise `operation`](BitwiseOperation) to the integers on top of the stack of incomming signals.
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+pub struct Bitwise {
+    pub operation: BitwiseOperation,
+}
+
+impl Bitwise {
+    pub fn new(operation: BitwiseOperation) -> Self {
+        Self { operation }
+    }
+}
+
+impl Tile for Bitwise {
+    fn update<'b>(&'b mut self, mut context: UpdateContext<'b>) {
+        if let Some(mut signal) = context.take_signal() {
+            let operation = self.operation;
+            signal.apply_to_stack(operation.arity(), |args| operation.apply(args));
+
+            let _ = context.send_direction(signal.direction(), signal);
+        }
+
+        if context.state() != State::Idle {
+            context.next_state();
+        }
+    }
+
+    fn draw_simple(&self, state: State) -> TextChar {
+        TextChar::from_state(self.operation.mnemonic(), state)
+    }
+}
+
+/// The operations that a [`Logic`] tile can apply.
+/// Their arguments can be any value, which are interpreted using [`Value::is_truthy`], and their result is a [`Bool`](Value::Bool).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum LogicOperation {
+    /// Whether both `a` and `b` are true
+    #[default]
+    And,
+    /// Whether `a` or `b` is true
+    Or,
+    /// Whether `x` is false
+    Not,
+}
+
+impl LogicOperation {
+    /// Returns the number of values that the operation pops from the stack
+    pub fn arity(self) -> usize {
+        match self {
+            LogicOperation::Not => 1,
+            _ => 2,
+        }
+    }
+
+    /// Returns the character used to draw a [`Logic`] tile applying this operation
+    pub fn mnemonic(self) -> char {
+        match self {
+            LogicOperation::And => '\u{2227}', // Logical And
+            LogicOperation::Or => '\u{2228}',  // Logical Or
+            LogicOperation::Not => '\u{00ac}', // Not Sign
+        }
+    }
+
+    /// Applies the operation to `args`, which must contain [`arity`](LogicOperation::arity) values.
+    pub fn apply(self, args: &[Value]) -> Result<Value, String> {
+        let result = match (self, args) {
+            (LogicOperation::And, [a, b]) => a.is_truthy() && b.is_truthy(),
+            (LogicOperation::Or, [a, b]) => a.is_truthy() || b.is_truthy(),
+            (LogicOperation::Not, [x]) => !x.is_truthy(),
+            _ => {
+                return Err(format!(
+                    "{:?}: expected {} values, found {}",
+                    self,
+                    self.arity(),
+                    args.len()
+                ))
+            }
+        };
+
+        Ok(Value::Bool(result))
+    }
+}
+
+/// Applies a [logical `operation`](LogicOperation) to the values on top of the stack of incomming signals.
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+pub struct Logic {
+    pub operation: LogicOperation,
+}
+
+impl Logic {
+    pub fn new(operation: LogicOperation) -> Self {
+        Self { operation }
+    }
+}
+
+impl Tile for Logic {
+    fn update<'b>(&'b mut self, mut context: UpdateContext<'b>) {
+        if let Some(mut signal) = context.take_signal() {
+            let operation = self.operation;
+            signal.apply_to_stack(operation.arity(), |args| operation.apply(args));
+
+            let _ = context.send_direction(signal.direction(), signal);
+        }
+
+        if context.state() != State::Idle {
+            context.next_state();
+        }
+    }
+
+    fn draw_simple(&self, state: State) -> TextChar {
+        TextChar::from_state(self.operation.mnemonic(), state)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_bitwise_operation() {
+        use BitwiseOperation::*;
+
+        let apply = |operation: BitwiseOperation, args: Vec<Value>| operation.apply(&args);
+
+        assert_eq!(
+            apply(And, vec![Value::Int(0b1100), Value::Int(0b1010)]),
+            Ok(Value::Int(0b1000))
+        );
+        assert_eq!(
+            apply(Or, vec![Value::Int(0b1100), Value::Int(0b1010)]),
+            Ok(Value::Int(0b1110))
+        );
+        assert_eq!(
+            apply(Xor, vec![Value::Int(0b1100), Value::Number(10.5)]),
+            Ok(Value::Int(0b0110))
+        );
+        assert_eq!(apply(Not, vec![Value::Int(0)]), Ok(Value::Int(-1)));
+        assert_eq!(
+            apply(ShiftLeft, vec![Value::Int(3), Value::Int(4)]),
+            Ok(Value::Int(48))
+        );
+        assert_eq!(
+            apply(ShiftLeft, vec![Value::Int(1), Value::Int(63)]),
+            Ok(Value::Int(i64::MIN))
+        );
+        assert_eq!(
+            apply(ShiftRight, vec![Value::Int(-16), Value::Int(2)]),
+            Ok(Value::Int(-4))
+        );
+
+        assert!(apply(ShiftLeft, vec![Value::Int(1), Value::Int(64)]).is_err());
+        assert!(apply(ShiftRight, vec![Value::Int(1), Value::Int(-1)]).is_err());
+        assert!(apply(And, vec![Value::Int(1), Value::from("a")]).is_err());
+        assert!(apply(Not, vec![]).is_err());
+    }
+
+    #[test]
+    fn test_logic_operation() {
+        use LogicOperation::*;
+
+        let apply = |operation: LogicOperation, args: Vec<Value>| operation.apply(&args);
+
+        assert_eq!(
+            apply(And, vec![Value::Int(1), Value::from("a")]),
+            Ok(Value::Bool(true))
+        );
+        assert_eq!(
+            apply(And, vec![Value::Int(1), Value::Nil]),
+            Ok(Value::Bool(false))
+        );
+        assert_eq!(
+            apply(Or, vec![Value::Bool(false), Value::Number(0.5)]),
+            Ok(Value::Bool(true))
+        );
+        assert_eq!(apply(Not, vec![Value::List(vec![])]), Ok(Value::Bool(true)));
+        assert!(apply(Or, vec![Value::Bool(true)]).is_err());
+    }
+
+    #[test]
+    fn test_logic() {
+        use crate::Wire;
+        use Direction::*;
+        use Orientation::*;
+
+        let mut pane = test_tile_setup!(
+            3,
+            1,
+            [
+                Bitwise::new(BitwiseOperation::Xor),
+                Logic::new(LogicOperation::Not),
+                Wire::new(Horizontal)
+            ]
+        );
+
+        // 5 ^ 5 is 0, which is false
+        pane.set_signal((0, 0), crate::signal!((0, 0), Right, [1, 5, 5]));
+        pane.step();
+        assert_eq!(
+            pane.get((1, 0)).unwrap().signal().unwrap().stack(),
+            &vec![Value::Int(1), Value::Int(0)]
+        );
+        pane.step();
+        assert_signal!(pane, (2, 0));
+        let signal = pane.get((2, 0)).unwrap().signal().cloned().unwrap();
+        assert_eq!(signal.stack(), &vec![Value::Int(1), Value::Bool(true)]);
+
+        for _ in 0..3 {
+            pane.step();
+        }
+
+        // Not enough values: the stack is left untouched
+        pane.set_signal((0, 0), crate::signal!((0, 0), Right, [5]));
+        pane.step();
+        assert_signal!(pane, (1, 0));
+        let signal = pane.get((1, 0)).unwrap().signal().cloned().unwrap();
+        assert_eq!(signal.error(), Some("Xor: expected 2 values, found 1"));
+        assert_eq!(signal.stack(), &vec![Value::Int(5)]);
+    }
+}
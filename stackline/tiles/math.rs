@@ -0,0 +1,251 @@
+//! Math tiles: apply mathematical functions to the values on top of the stack
+
+use crate::prelude::*;
+use crate::tile::prelude::*;
+
+/// The functions that a [`Math`] tile can apply.
+///
+/// Unary functions pop one value `x`, binary functions pop `b`, then `a` (so `a` is the value that was pushed first).
+/// The values must be numbers, as given by [`Value::as_number`].
+///
+/// [`Abs`](MathFunction::Abs), [`Floor`](MathFunction::Floor), [`Ceil`](MathFunction::Ceil), [`Round`](MathFunction::Round),
+/// [`Min`](MathFunction::Min) and [`Max`](MathFunction::Max) return an [`Int`](Value::Int) if all of their arguments are integers;
+/// every other result is a [`Number`](Value::Number).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum MathFunction {
+    /// Square root of `x`
+    #[default]
+    Sqrt,
+    /// `a` to the power of `b`
+    Pow,
+    /// Absolute value of `x`
+    Abs,
+    /// Largest integer less than or equal to `x`
+    Floor,
+    /// Smallest integer greater than or equal to `x`
+    Ceil,
+    /// Nearest integer to `x`, rounding half-way cases away from zero
+    Round,
+    /// Sine of `x`, in radians
+    Sin,
+    /// Cosine of `x`, in radians
+    Cos,
+    /// Smallest of `a` and `b`
+    Min,
+    /// Largest of `a` and `b`
+    Max,
+}
+
+impl MathFunction {
+    /// Returns the number of values that the function pops from the stack
+    pub fn arity(self) -> usize {
+        match self {
+            MathFunction::Pow | MathFunction::Min | MathFunction::Max => 2,
+            _ => 1,
+        }
+    }
+
+    /// Returns the character used to draw a [`Math`] tile applying this function
+    pub fn mnemonic(self) -> char {
+        match self {
+            MathFunction::Sqrt => '\u{221a}',  // Square Root
+            MathFunction::Pow => '\u{207f}',   // Superscript Latin Small Letter N
+            MathFunction::Abs => '\u{2223}',   // Divides
+            MathFunction::Floor => '\u{230a}', // Left Floor
+            MathFunction::Ceil => '\u{2308}',  // Left Ceiling
+            MathFunction::Round => '\u{2248}', // Almost Equal To
+            MathFunction::Sin => 's',
+            MathFunction::Cos => 'c',
+            MathFunction::Min => 'm',
+            MathFunction::Max => 'M',
+        }
+    }
+
+    /// Applies the function to `args`, which must contain [`arity`](MathFunction::arity) values.
+    /// Returns an error message if the arguments aren't numbers, if the result overflows an integer or if the result is NaN.
+    pub fn apply(self, args: &[Value]) -> Result<Value, String> {
+        let name = format!("{:?}", self);
+
+        if args.len() != self.arity() {
+            return Err(format!(
+                "{}: expected {} values, found {}",
+                name,
+                self.arity(),
+                args.len()
+            ));
+        }
+
+        // Integer arguments are kept as integers where possible
+        match (self, args) {
+            (MathFunction::Abs, [Value::Int(x)]) => {
+                return x
+                    .checked_abs()
+                    .map(Value::Int)
+                    .ok_or_else(|| format!("{}: integer overflow", name));
+            }
+            (MathFunction::Floor | MathFunction::Ceil | MathFunction::Round, [Value::Int(x)]) => {
+                return Ok(Value::Int(*x));
+            }
+            (MathFunction::Min, [Value::Int(a), Value::Int(b)]) => {
+                return Ok(Value::Int(*a.min(b)));
+            }
+            (MathFunction::Max, [Value::Int(a), Value::Int(b)]) => {
+                return Ok(Value::Int(*a.max(b)));
+            }
+            _ => {}
+        }
+
+        let numbers = args
+            .iter()
+            .map(Value::as_number)
+            .collect::<Option<Vec<f64>>>()
+            .ok_or_else(|| format!("{}: expected numbers", name))?;
+
+        let result = match (self, numbers.as_slice()) {
+            (MathFunction::Sqrt, [x]) => x.sqrt(),
+            (MathFunction::Pow, [a, b]) => a.powf(*b),
+            (MathFunction::Abs, [x]) => x.abs(),
+            (MathFunction::Floor, [x]) => x.floor(),
+            (MathFunction::Ceil, [x]) => x.ceil(),
+            (MathFunction::Round, [x]) => x.round(),
+            (MathFunction::Sin, [x]) => x.sin(),
+            (MathFunction::Cos, [x]) => x.cos(),
+            (MathFunction::Min, [a, b]) => a.min(*b),
+            (MathFunction::Max, [a, b]) => a.max(*b),
+            _ => unreachable!(),
+        };
+
+        if result.is_nan() {
+            Err(format!("{}: result is not a number", name))
+        } else {
+            Ok(Value::Number(result))
+        }
+    }
+}
+
+/// Applies `function` to the values on top of the stack of incomming signals and pushes the result in their place.
+/// The signal then keeps going in the same direction.
+///
+/// If the function fails (see [`MathFunction::apply`]), then the stack is left untouched
+/// and the signal is marked as [errored](Signal::set_error).
+/// In particular, NaN is never pushed: operations like the square root of a negative number are errors.
+/// Infinite results, like `10 ^ 1000`, are valid and are pushed as-is; so are infinite and NaN arguments
+/// for functions that give a non-NaN result with them, like `min`.
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+pub struct Math {
+    pub function: MathFunction,
+}
+
+impl Math {
+    pub fn new(function: MathFunction) -> Self {
+        Self { function }
+    }
+}
+
+impl Tile for Math {
+    fn update<'b>(&'b mut self, mut context: UpdateContext<'b>) {
+        if let Some(mut signal) = context.take_signal() {
+            let function = self.function;
+            signal.apply_to_stack(function.arity(), |args| function.apply(args));
+
+            let _ = context.send_direction(signal.direction(), signal);
+        }
+
+        if context.state() != State::Idle {
+            context.next_state();
+        }
+    }
+
+    fn draw_simple(&self, state: State) -> TextChar {
+        TextChar::from_state(self.function.mnemonic(), state)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use MathFunction::*;
+
+    fn apply(function: MathFunction, args: Vec<Value>) -> Result<Value, String> {
+        function.apply(&args)
+    }
+
+    #[test]
+    fn test_math_function() {
+        assert_eq!(apply(Sqrt, vec![Value::Int(9)]), Ok(Value::Number(3.0)));
+        assert_eq!(
+            apply(Pow, vec![Value::Int(2), Value::Number(0.5)]),
+            Ok(Value::Number(2f64.sqrt()))
+        );
+        assert_eq!(apply(Abs, vec![Value::Int(-3)]), Ok(Value::Int(3)));
+        assert_eq!(
+            apply(Abs, vec![Value::Number(-1.5)]),
+            Ok(Value::Number(1.5))
+        );
+        assert_eq!(
+            apply(Floor, vec![Value::Number(-1.5)]),
+            Ok(Value::Number(-2.0))
+        );
+        assert_eq!(
+            apply(Ceil, vec![Value::Number(1.2)]),
+            Ok(Value::Number(2.0))
+        );
+        assert_eq!(
+            apply(Round, vec![Value::Number(2.5)]),
+            Ok(Value::Number(3.0))
+        );
+        assert_eq!(apply(Round, vec![Value::Int(7)]), Ok(Value::Int(7)));
+        assert_eq!(apply(Sin, vec![Value::Int(0)]), Ok(Value::Number(0.0)));
+        assert_eq!(apply(Cos, vec![Value::Int(0)]), Ok(Value::Number(1.0)));
+        assert_eq!(
+            apply(Min, vec![Value::Int(2), Value::Int(-1)]),
+            Ok(Value::Int(-1))
+        );
+        assert_eq!(
+            apply(Max, vec![Value::Int(2), Value::Number(2.5)]),
+            Ok(Value::Number(2.5))
+        );
+
+        // NaN and infinity
+        assert!(apply(Sqrt, vec![Value::Int(-1)]).is_err());
+        assert!(apply(Sin, vec![Value::Number(f64::INFINITY)]).is_err());
+        assert_eq!(
+            apply(Pow, vec![Value::Int(10), Value::Int(1000)]),
+            Ok(Value::Number(f64::INFINITY))
+        );
+        assert_eq!(
+            apply(Min, vec![Value::Number(f64::NAN), Value::Int(1)]),
+            Ok(Value::Number(1.0))
+        );
+
+        // Invalid arguments
+        assert!(apply(Abs, vec![Value::Int(i64::MIN)]).is_err());
+        assert!(apply(Sqrt, vec![Value::from("a")]).is_err());
+    }
+
+    #[test]
+    fn test_math() {
+        use crate::Wire;
+        use Direction::*;
+        use Orientation::*;
+
+        let mut pane = test_tile_setup!(2, 1, [Math::new(Pow), Wire::new(Horizontal)]);
+
+        pane.set_signal((0, 0), crate::signal!((0, 0), Right, [1, 2, 3]));
+        pane.step();
+        assert_signal!(pane, (1, 0));
+        let signal = pane.get((1, 0)).unwrap().signal().cloned().unwrap();
+        assert_eq!(signal.stack(), &vec![Value::Int(1), Value::Number(8.0)]);
+
+        pane.step();
+        pane.step();
+
+        // The stack is left untouched on errors
+        pane.set_signal((0, 0), crate::signal!((0, 0), Right, [-1, 0.5]));
+        pane.step();
+        assert_signal!(pane, (1, 0));
+        let signal = pane.get((1, 0)).unwrap().signal().cloned().unwrap();
+        assert_eq!(signal.error(), Some("Pow: result is not a number"));
+        assert_eq!(signal.stack(), &vec![Value::Int(-1), Value::Number(0.5)]);
+    }
+}
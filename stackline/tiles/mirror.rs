@@ -0,0 +1,205 @@
+//! Deflecting tiles: turn signals by 90 degrees
+
+use crate::prelude::*;
+use crate::tile::prelude::*;
+
+/// Reflects incomming signals like a `/` mirror would:
+/// signals travelling right are sent up, signals travelling up are sent right,
+/// signals travelling left are sent down and signals travelling down are sent left.
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+pub struct MirrorSlash;
+
+impl MirrorSlash {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Tile for MirrorSlash {
+    fn update<'b>(&'b mut self, mut context: UpdateContext<'b>) {
+        if let Some(signal) = context.take_signal() {
+            let direction = match signal.direction() {
+                Direction::Right => Direction::Up,
+                Direction::Up => Direction::Right,
+                Direction::Left => Direction::Down,
+                Direction::Down => Direction::Left,
+            };
+
+            let _ = context.send_direction(direction, signal.moved(direction));
+        }
+
+        if context.state() != State::Idle {
+            context.next_state();
+        }
+    }
+
+    fn draw_simple(&self, state: State) -> TextChar {
+        TextChar::from_state('/', state)
+    }
+}
+
+/// Reflects incomming signals like a `\` mirror would:
+/// signals travelling right are sent down, signals travelling down are sent right,
+/// signals travelling left are sent up and signals travelling up are sent left.
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+pub struct MirrorBackslash;
+
+impl MirrorBackslash {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Tile for MirrorBackslash {
+    fn update<'b>(&'b mut self, mut context: UpdateContext<'b>) {
+        if let Some(signal) = context.take_signal() {
+            let direction = match signal.direction() {
+                Direction::Right => Direction::Down,
+                Direction::Down => Direction::Right,
+                Direction::Left => Direction::Up,
+                Direction::Up => Direction::Left,
+            };
+
+            let _ = context.send_direction(direction, signal.moved(direction));
+        }
+
+        if context.state() != State::Idle {
+            context.next_state();
+        }
+    }
+
+    fn draw_simple(&self, state: State) -> TextChar {
+        TextChar::from_state('\\', state)
+    }
+}
+
+/// Turns incomming signals by 90 degrees counter-clockwise, see [`Direction::rotate_left`].
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+pub struct RotateLeft;
+
+impl RotateLeft {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Tile for RotateLeft {
+    fn update<'b>(&'b mut self, mut context: UpdateContext<'b>) {
+        if let Some(signal) = context.take_signal() {
+            let direction = signal.direction().rotate_left();
+
+            let _ = context.send_direction(direction, signal.moved(direction));
+        }
+
+        if context.state() != State::Idle {
+            context.next_state();
+        }
+    }
+
+    fn draw_simple(&self, state: State) -> TextChar {
+        TextChar::from_state('\u{21ba}', state) // Anticlockwise Open Circle Arrow
+    }
+}
+
+/// Turns incomming signals by 90 degrees clockwise, see [`Direction::rotate_right`].
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+pub struct RotateRight;
+
+impl RotateRight {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Tile for RotateRight {
+    fn update<'b>(&'b mut self, mut context: UpdateContext<'b>) {
+        if let Some(signal) = context.take_signal() {
+            let direction = signal.direction().rotate_right();
+
+            let _ = context.send_direction(direction, signal.moved(direction));
+        }
+
+        if context.state() != State::Idle {
+            context.next_state();
+        }
+    }
+
+    fn draw_simple(&self, state: State) -> TextChar {
+        TextChar::from_state('\u{21bb}', state) // Clockwise Open Circle Arrow
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Wire;
+    use Direction::*;
+
+    /// Sends a signal travelling towards `direction` through `tile`, and returns the direction in which it left the tile
+    fn deflect(tile: impl Into<FullTile>, direction: Direction) -> Direction {
+        let tile: FullTile = tile.into();
+        let mut pane = test_tile_setup!(
+            3,
+            3,
+            [
+                (),
+                Wire::new(Orientation::Any),
+                (),
+                Wire::new(Orientation::Any),
+                tile,
+                Wire::new(Orientation::Any),
+                (),
+                Wire::new(Orientation::Any),
+                (),
+            ]
+        );
+
+        test_set_signal!(pane, (1, 1), direction);
+        pane.step();
+
+        let outputs: Vec<Direction> = [Up, Down, Left, Right]
+            .into_iter()
+            .filter(|direction| {
+                let position = pane.offset((1, 1), direction.into_offset()).unwrap();
+
+                match pane.get(position).unwrap().signal() {
+                    Some(signal) => {
+                        assert_eq!(signal.direction(), *direction);
+                        true
+                    }
+                    None => false,
+                }
+            })
+            .collect();
+        assert_eq!(outputs.len(), 1);
+
+        outputs[0]
+    }
+
+    #[test]
+    fn test_mirrors() {
+        assert_eq!(deflect(MirrorSlash::new(), Right), Up);
+        assert_eq!(deflect(MirrorSlash::new(), Up), Right);
+        assert_eq!(deflect(MirrorSlash::new(), Left), Down);
+        assert_eq!(deflect(MirrorSlash::new(), Down), Left);
+
+        assert_eq!(deflect(MirrorBackslash::new(), Right), Down);
+        assert_eq!(deflect(MirrorBackslash::new(), Down), Right);
+        assert_eq!(deflect(MirrorBackslash::new(), Left), Up);
+        assert_eq!(deflect(MirrorBackslash::new(), Up), Left);
+    }
+
+    #[test]
+    fn test_rotate() {
+        for direction in [Up, Down, Left, Right] {
+            assert_eq!(
+                deflect(RotateLeft::new(), direction),
+                direction.rotate_left()
+            );
+            assert_eq!(
+                deflect(RotateRight::new(), direction),
+                direction.rotate_right()
+            );
+        }
+    }
+}
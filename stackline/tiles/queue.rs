@@ -0,0 +1,454 @@
+//! Buffering tiles: hold several signals and release them over time, or on demand
+
+use crate::prelude::*;
+use crate::tile::prelude::*;
+use std::collections::VecDeque;
+
+/// Holds a first-in, first-out queue of signals, and releases them towards `direction`.
+/// Signals may enter the queue from every side except the one it releases signals towards,
+/// and every signal is queued, even if several of them arrive during the same update cycle.
+///
+/// - if `gated` is `false`, then at most one signal is released per update cycle
+/// - if `gated` is `true`, then the sides perpendicular to `direction` are control sides:
+///   signals entering from them are dropped, and each of them lets one queued signal be released.
+///   Control signals arriving while the queue holds fewer signals have no effect.
+///
+/// Signals are only released if the tile in `direction`, or the tile found through the edge of a [connected](Pane::connected) pane,
+/// accepts them; otherwise, they stay in the queue.
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+pub struct Queue {
+    pub direction: Direction,
+    pub gated: bool,
+    pub signals: VecDeque<Signal>,
+    /// Number of signals that control signals allowed to be released
+    #[serde(default)]
+    pub releases: usize,
+}
+
+impl Queue {
+    pub fn new(direction: Direction, gated: bool) -> Self {
+        Self {
+            direction,
+            gated,
+            signals: VecDeque::new(),
+            releases: 0,
+        }
+    }
+
+    fn is_control(&self, direction: Direction) -> bool {
+        self.gated && direction != self.direction && direction != self.direction.opposite()
+    }
+}
+
+impl Tile for Queue {
+    fn update<'b>(&'b mut self, mut context: UpdateContext<'b>) {
+        // Signals are absorbed into `signals`, but may still be set directly on the tile
+        if let Some(signal) = context.take_signal() {
+            let _ = self.absorb_signal(signal);
+        }
+
+        let release = !self.gated || self.releases > 0;
+        let accepted = context.accepts_direction(self.direction).is_some()
+            || context.accepts_edge(self.direction);
+        if release && accepted {
+            if let Some(signal) = self.signals.pop_front() {
+                self.releases = self.releases.saturating_sub(1);
+                let _ = context.send_direction(self.direction, signal.moved(self.direction));
+            }
+        }
+        self.releases = self.releases.min(self.signals.len());
+
+        if !self.signals.is_empty() {
+            context.set_state(State::Active);
+        } else if context.state() != State::Idle {
+            context.next_state();
+        }
+    }
+
+    fn absorb_signal(&mut self, signal: Signal) -> Option<Signal> {
+        if self.is_control(signal.direction()) {
+            self.releases += 1;
+        } else {
+            self.signals.push_back(signal);
+        }
+
+        None
+    }
+
+    fn accepts_signal(&self, direction: Direction) -> bool {
+        direction != self.direction.opposite()
+    }
+
+    fn accepts_while_active(&self) -> bool {
+        true
+    }
+
+    fn draw_simple(&self, state: State) -> TextChar {
+        let ch = match self.signals.len() {
+            0 => 'Q',
+            n @ 1..=9 => char::from_digit(n as u32, 10).unwrap(),
+            _ => '\u{2026}', // Horizontal Ellipsis
+        };
+
+        TextChar::from_state(ch, state)
+    }
+}
+
+/// Lets at most `count` signals travelling towards `direction` through, until they are released; it acts as a lock if `count` is 1,
+/// and as a semaphore otherwise.
+///
+/// - signals travelling towards `direction` are sent through if fewer than `count` signals are currently holding the lock;
+///   otherwise, they wait in a first-in, first-out queue
+/// - signals are only sent through if the tile in `direction`, or the tile found through the edge of a [connected](Pane::connected) pane,
+///   accepts them; otherwise, they keep waiting
+/// - signals entering the lock from either side are release signals, which are consumed by the lock:
+///   they release one of the signals holding the lock, letting the first waiting signal through
+///
+/// Every signal is taken into account, even if several of them arrive during the same update cycle.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Lock {
+    pub direction: Direction,
+    pub count: usize,
+    /// Number of signals currently holding the lock
+    pub held: usize,
+    pub waiting: VecDeque<Signal>,
+}
+
+impl Lock {
+    pub fn new(direction: Direction, count: usize) -> Self {
+        Self {
+            direction,
+            count,
+            held: 0,
+            waiting: VecDeque::new(),
+        }
+    }
+
+    fn is_release(&self, direction: Direction) -> bool {
+        direction != self.direction && direction != self.direction.opposite()
+    }
+}
+
+impl Default for Lock {
+    fn default() -> Self {
+        Self::new(Direction::default(), 1)
+    }
+}
+
+impl Tile for Lock {
+    fn update<'b>(&'b mut self, mut context: UpdateContext<'b>) {
+        // Signals are absorbed into `waiting`, but may still be set directly on the tile
+        if let Some(signal) = context.take_signal() {
+            let _ = self.absorb_signal(signal);
+        }
+
+        let accepted = context.accepts_direction(self.direction).is_some()
+            || context.accepts_edge(self.direction);
+        if self.held < self.count && accepted {
+            if let Some(signal) = self.waiting.pop_front() {
+                self.held += 1;
+                let _ = context.send_direction(self.direction, signal.moved(self.direction));
+            }
+        }
+
+        if !self.waiting.is_empty() {
+            context.set_state(State::Active);
+        } else if context.state() != State::Idle {
+            context.next_state();
+        }
+    }
+
+    fn absorb_signal(&mut self, signal: Signal) -> Option<Signal> {
+        if self.is_release(signal.direction()) {
+            self.held = self.held.saturating_sub(1);
+        } else {
+            self.waiting.push_back(signal);
+        }
+
+        None
+    }
+
+    fn accepts_signal(&self, direction: Direction) -> bool {
+        direction == self.direction || self.is_release(direction)
+    }
+
+    fn accepts_while_active(&self) -> bool {
+        true
+    }
+
+    fn draw_simple(&self, state: State) -> TextChar {
+        let ch = if self.held < self.count {
+            '\u{25a1}' // White Square
+        } else {
+            '\u{25a0}' // Black Square
+        };
+
+        TextChar::from_state(ch, state)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_queue() {
+        use crate::{Diode, Wire};
+        use Direction::*;
+        use Orientation::*;
+
+        let mut pane = test_tile_setup!(
+            3,
+            1,
+            [
+                Diode::new(Right),
+                Queue::new(Right, false),
+                Wire::new(Horizontal)
+            ]
+        );
+
+        // A burst of signals, arriving faster than the wire can carry them:
+        // the wire only accepts one signal every three update cycles, the others stay in the queue
+        let mut received = Vec::new();
+        for n in 0..15 {
+            if n < 3 {
+                pane.set_signal((1, 0), crate::signal!((1, 0), Right, [n]));
+            }
+            pane.step();
+
+            if n == 2 {
+                assert_eq!(pane.get_as::<Queue>((1, 0)).unwrap().signals.len(), 2);
+            }
+            if let Some(signal) = pane.get((2, 0)).unwrap().signal() {
+                received.push(signal.stack()[0].clone());
+            }
+        }
+
+        assert_eq!(received, vec![Value::Int(0), Value::Int(1), Value::Int(2)]);
+        assert!(pane.get_as::<Queue>((1, 0)).unwrap().signals.is_empty());
+        assert_eq!(pane.get((1, 0)).unwrap().state(), State::Idle);
+    }
+
+    #[test]
+    fn test_queue_gated() {
+        use crate::Wire;
+        use Direction::*;
+        use Orientation::*;
+
+        let mut pane = test_tile_setup!(
+            3,
+            2,
+            [
+                (),
+                Queue::new(Right, true),
+                Wire::new(Horizontal),
+                (),
+                (),
+                (),
+            ]
+        );
+
+        test_set_signal!(pane, (1, 0), Right);
+        pane.step();
+        test_set_signal!(pane, (1, 0), Right);
+        pane.step();
+
+        for _ in 0..4 {
+            pane.step();
+        }
+        assert_no_signal!(pane, (2, 0));
+        assert_eq!(pane.get_as::<Queue>((1, 0)).unwrap().signals.len(), 2);
+
+        // A control signal releases one signal
+        assert!(pane.get((1, 0)).unwrap().accepts_signal(Up));
+        assert!(!pane.get((1, 0)).unwrap().accepts_signal(Left));
+        test_set_signal!(pane, (1, 0), Up);
+        pane.step();
+        assert_signal!(pane, (2, 0));
+        assert_eq!(pane.get_as::<Queue>((1, 0)).unwrap().signals.len(), 1);
+
+        for _ in 0..3 {
+            pane.step();
+        }
+
+        // A data signal and a control signal arriving during the same update cycle are both taken into account
+        test_set_signal!(pane, (1, 0), Right);
+        test_set_signal!(pane, (1, 0), Down);
+        assert_eq!(pane.get_as::<Queue>((1, 0)).unwrap().signals.len(), 2);
+        pane.step();
+        assert_signal!(pane, (2, 0));
+        assert_eq!(pane.get_as::<Queue>((1, 0)).unwrap().signals.len(), 1);
+        assert_eq!(pane.get_as::<Queue>((1, 0)).unwrap().releases, 0);
+    }
+
+    #[test]
+    fn test_queue_converging() {
+        use crate::Wire;
+        use Direction::*;
+        use Orientation::*;
+
+        let mut pane = test_tile_setup!(
+            3,
+            3,
+            [
+                (),
+                Wire::new(Vertical),
+                (),
+                Wire::new(Horizontal),
+                Queue::new(Right, false),
+                Wire::new(Horizontal),
+                (),
+                Wire::new(Vertical),
+                (),
+            ]
+        );
+
+        // Three signals reach the queue during the same update cycle
+        pane.set_signal((1, 0), crate::signal!((1, 0), Down, [0]));
+        pane.set_signal((0, 1), crate::signal!((0, 1), Right, [1]));
+        pane.set_signal((1, 2), crate::signal!((1, 2), Up, [2]));
+        pane.step();
+        assert_eq!(pane.get_as::<Queue>((1, 1)).unwrap().signals.len(), 3);
+
+        let mut received = Vec::new();
+        for _ in 0..12 {
+            pane.step();
+            if let Some(signal) = pane.get((2, 1)).unwrap().signal() {
+                received.push(signal.stack()[0].clone());
+            }
+        }
+
+        assert_eq!(received.len(), 3);
+        assert!(pane.get_as::<Queue>((1, 1)).unwrap().signals.is_empty());
+    }
+
+    #[test]
+    fn test_queue_edge() {
+        use crate::Wire;
+        use Direction::*;
+        use Orientation::*;
+
+        let mut main = test_tile_setup!(1, 1, [Queue::new(Right, false)]);
+        main.set_connected(true);
+
+        let mut world = World::new();
+        world.set_pane(String::from("main"), main);
+        world
+            .get_pane_mut("main")
+            .unwrap()
+            .set_signal((0, 0), crate::signal!((0, 0), Right, [0]));
+
+        // There is no pane behind the edge: the signal stays in the queue
+        for _ in 0..3 {
+            world.step();
+        }
+        let queue = world.get_pane("main").unwrap().get_as::<Queue>((0, 0));
+        assert_eq!(queue.unwrap().signals.len(), 1);
+
+        let mut right = test_tile_setup!(1, 1, [Wire::new(Horizontal)]);
+        right.set_position((1, 0));
+        world.set_pane(String::from("right"), right);
+
+        world.step();
+        assert!(world.get((1, 0)).unwrap().signal().is_some());
+        let queue = world.get_pane("main").unwrap().get_as::<Queue>((0, 0));
+        assert!(queue.unwrap().signals.is_empty());
+    }
+
+    #[test]
+    fn test_lock() {
+        use crate::Wire;
+        use Direction::*;
+        use Orientation::*;
+
+        let mut pane = test_tile_setup!(3, 1, [(), Lock::new(Right, 2), Wire::new(Horizontal)]);
+
+        // The first signal goes through
+        test_set_signal!(pane, (1, 0), Right);
+        pane.step();
+        assert_signal!(pane, (2, 0));
+        assert_eq!(pane.get_as::<Lock>((1, 0)).unwrap().held, 1);
+
+        // The wire refuses the second signal while it is active: the signal keeps waiting and the lock isn't taken
+        test_set_signal!(pane, (1, 0), Right);
+        pane.step();
+        assert_no_signal!(pane, (2, 0));
+        {
+            let lock = pane.get_as::<Lock>((1, 0)).unwrap();
+            assert_eq!(lock.waiting.len(), 1);
+            assert_eq!(lock.held, 1);
+        }
+
+        // Once the wire is idle again, the second signal goes through
+        let mut received = false;
+        for _ in 0..3 {
+            pane.step();
+            received |= pane.get((2, 0)).unwrap().signal().is_some();
+        }
+        assert!(received);
+        assert_eq!(pane.get_as::<Lock>((1, 0)).unwrap().held, 2);
+
+        // The third signal waits until the lock is released
+        test_set_signal!(pane, (1, 0), Right);
+        for _ in 0..4 {
+            pane.step();
+            assert_no_signal!(pane, (2, 0));
+        }
+        assert_eq!(pane.get_as::<Lock>((1, 0)).unwrap().waiting.len(), 1);
+
+        // A release signal lets the waiting signal through
+        test_set_signal!(pane, (1, 0), Down);
+        pane.step();
+        assert_signal!(pane, (2, 0));
+
+        {
+            let lock = pane.get_as::<Lock>((1, 0)).unwrap();
+            assert!(lock.waiting.is_empty());
+            assert_eq!(lock.held, 2);
+        }
+
+        for _ in 0..3 {
+            pane.step();
+        }
+
+        // A signal and a release signal arriving during the same update cycle are both taken into account
+        test_set_signal!(pane, (1, 0), Right);
+        test_set_signal!(pane, (1, 0), Up);
+        {
+            let lock = pane.get_as::<Lock>((1, 0)).unwrap();
+            assert_eq!(lock.waiting.len(), 1);
+            assert_eq!(lock.held, 1);
+        }
+        pane.step();
+        assert_signal!(pane, (2, 0));
+
+        let lock = pane.get_as::<Lock>((1, 0)).unwrap();
+        assert!(lock.waiting.is_empty());
+        assert_eq!(lock.held, 2);
+    }
+
+    #[test]
+    fn test_lock_edge() {
+        let mut main = test_tile_setup!(1, 1, [Lock::new(Direction::Right, 1)]);
+        main.set_connected(true);
+
+        let mut world = World::new();
+        world.set_pane(String::from("main"), main);
+        world
+            .get_pane_mut("main")
+            .unwrap()
+            .set_signal((0, 0), Signal::empty((0, 0), Direction::Right));
+
+        // There is no pane behind the edge: the signal keeps waiting and the lock isn't taken
+        for _ in 0..3 {
+            world.step();
+        }
+        let lock = world
+            .get_pane("main")
+            .unwrap()
+            .get_as::<Lock>((0, 0))
+            .unwrap();
+        assert_eq!(lock.waiting.len(), 1);
+        assert_eq!(lock.held, 0);
+    }
+}
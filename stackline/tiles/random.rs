@@ -0,0 +1,264 @@
+//! Random tiles: pseudo-random values and routing, driven by the seed of the world
+//!
+//! See [`UpdateContext::random`] for how the pseudo-random numbers are generated.
+
+use crate::prelude::*;
+use crate::tile::prelude::*;
+
+/// Pushes a pseudo-random integer between `min` and `max` (both included) onto the stack of incomming signals.
+/// The signal then keeps going in the same direction.
+///
+/// If `min` is greater than `max`, then the signal is marked as [errored](Signal::set_error).
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+pub struct Random {
+    pub min: i64,
+    pub max: i64,
+}
+
+impl Random {
+    pub fn new(min: i64, max: i64) -> Self {
+        Self { min, max }
+    }
+}
+
+impl Tile for Random {
+    fn update<'b>(&'b mut self, mut context: UpdateContext<'b>) {
+        if let Some(mut signal) = context.take_signal() {
+            if self.min <= self.max {
+                let span = self.max.wrapping_sub(self.min) as u64;
+                let offset = match span.checked_add(1) {
+                    Some(bound) => context.random_range(bound),
+                    None => context.random(),
+                };
+
+                signal.push(Value::Int(self.min.wrapping_add(offset as i64)));
+            } else {
+                signal.set_error(format!(
+                    "Random: min ({}) is greater than max ({})",
+                    self.min, self.max
+                ));
+            }
+
+            let _ = context.send_direction(signal.direction(), signal);
+        }
+
+        if context.state() != State::Idle {
+            context.next_state();
+        }
+    }
+
+    fn draw_simple(&self, state: State) -> TextChar {
+        TextChar::from_state('~', state)
+    }
+}
+
+/// Sends incomming signals towards a pseudo-randomly chosen direction, among the directions in which a neighbor accepts the signal.
+/// Directions that cross the edge of a [connected](Pane::connected) pane are candidates as well,
+/// if the tile found through that edge accepts the signal.
+/// Signals are never sent back to where they came from.
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+pub struct RandomRouter;
+
+impl RandomRouter {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Tile for RandomRouter {
+    fn update<'b>(&'b mut self, mut context: UpdateContext<'b>) {
+        if let Some(signal) = context.take_signal() {
+            let directions: Vec<Direction> = [
+                Direction::Up,
+                Direction::Right,
+                Direction::Down,
+                Direction::Left,
+            ]
+            .into_iter()
+            .filter(|&direction| {
+                direction != signal.direction().opposite()
+                    && (context.accepts_direction(direction).is_some()
+                        || context.accepts_edge(direction))
+            })
+            .collect();
+
+            if !directions.is_empty() {
+                let index = context.random_range(directions.len() as u64) as usize;
+                let direction = directions[index];
+
+                let _ = context.send_direction(direction, signal.moved(direction));
+            }
+        }
+
+        if context.state() != State::Idle {
+            context.next_state();
+        }
+    }
+
+    fn draw_simple(&self, state: State) -> TextChar {
+        TextChar::from_state('*', state)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_random() {
+        use crate::{Diode, Wire};
+        use Direction::*;
+        use Orientation::*;
+
+        fn run(seed: u64) -> Vec<Value> {
+            let mut pane = test_tile_setup!(
+                4,
+                1,
+                [
+                    Diode::new(Right),
+                    Random::new(1, 6),
+                    Random::new(-3, -3),
+                    Wire::new(Horizontal)
+                ]
+            );
+            test_set_signal!(pane, (0, 0), Right);
+
+            let mut world = World::new();
+            world.set_pane(String::from("main"), pane);
+            world.set_seed(seed);
+
+            for _ in 0..3 {
+                world.step();
+            }
+
+            let stack = world.get((3, 0)).unwrap().signal().unwrap().stack().clone();
+            stack
+        }
+
+        let stack = run(1);
+        let value = stack[0].as_int().unwrap();
+        assert!((1..=6).contains(&value));
+        assert_eq!(stack[1], Value::Int(-3));
+
+        // The same seed gives the same values
+        assert_eq!(run(1), stack);
+
+        // Other seeds give other values
+        assert!((2..20).any(|seed| run(seed) != stack));
+    }
+
+    #[test]
+    fn test_random_router() {
+        use crate::Wire;
+        use Direction::*;
+        use Orientation::*;
+
+        fn run(seed: u64) -> (i32, i32) {
+            let pane = test_tile_setup!(
+                3,
+                3,
+                [
+                    (),
+                    Wire::new(Vertical),
+                    (),
+                    Wire::new(Horizontal),
+                    RandomRouter::new(),
+                    Wire::new(Horizontal),
+                    (),
+                    Wire::new(Vertical),
+                    (),
+                ]
+            );
+
+            let mut world = World::new();
+            world.set_pane(String::from("main"), pane);
+            world.set_seed(seed);
+            world
+                .get_pane_mut("main")
+                .unwrap()
+                .set_signal((1, 1), Signal::empty((1, 1), Right));
+
+            world.step();
+
+            let mut found = None;
+            for position in [(1, 0), (2, 1), (1, 2), (0, 1)] {
+                if world.get(position).unwrap().signal().is_some() {
+                    assert!(found.is_none());
+                    found = Some(position);
+                }
+            }
+
+            found.unwrap()
+        }
+
+        let outputs: Vec<_> = (0..32).map(run).collect();
+
+        // Signals are never sent back
+        assert!(!outputs.contains(&(0, 1)));
+
+        // Every other direction gets picked
+        for position in [(1, 0), (2, 1), (1, 2)] {
+            assert!(outputs.contains(&position));
+        }
+
+        assert_eq!(outputs, (0..32).map(run).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_random_router_edge() {
+        use crate::Wire;
+        use Direction::*;
+        use Orientation::*;
+
+        fn run(seed: u64) -> Option<&'static str> {
+            let mut main = test_tile_setup!(2, 1, [RandomRouter::new(), Wire::new(Horizontal)]);
+            main.set_connected(true);
+            let mut above = test_tile_setup!(1, 1, [Wire::new(Vertical)]);
+            above.set_position((0, -1));
+
+            let mut world = World::new();
+            world.set_pane(String::from("main"), main);
+            world.set_pane(String::from("above"), above);
+            world.set_seed(seed);
+            world
+                .get_pane_mut("main")
+                .unwrap()
+                .set_signal((0, 0), Signal::empty((0, 0), Right));
+
+            world.step();
+
+            if world
+                .get_pane("above")
+                .unwrap()
+                .get((0, 0))
+                .unwrap()
+                .signal()
+                .is_some()
+            {
+                Some("above")
+            } else if world
+                .get_pane("main")
+                .unwrap()
+                .get((1, 0))
+                .unwrap()
+                .signal()
+                .is_some()
+            {
+                Some("right")
+            } else {
+                None
+            }
+        }
+
+        let outputs: Vec<_> = (0..32).map(run).collect();
+
+        // The signal goes to the wire or crosses the top edge into the other pane;
+        // the bottom edge has no pane behind it, so it is never picked and the signal is always delivered
+        assert!(outputs.contains(&Some("above")));
+        assert!(outputs.contains(&Some("right")));
+        assert!(!outputs.contains(&None));
+
+        // The same seed gives the same direction
+        assert_eq!((0..32).map(run).collect::<Vec<_>>(), outputs);
+    }
+}
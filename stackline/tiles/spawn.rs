@@ -0,0 +1,271 @@
+//! Spawning tiles: create and delete panes while the world is running
+
+use crate::prelude::*;
+use crate::tile::prelude::*;
+
+/// Instantiates a new copy of the pane named `source` at `position` for every incomming signal,
+/// then sends the signal into that copy, at `entry`.
+///
+/// Each copy is named after `name`, followed by a suffix that is unique within the world (see [`UpdateContext::unique_pane_name`]),
+/// so that a pane spawning copies of itself gets a fresh pane for every call.
+/// The name of the copy is [recorded](Signal::push_spawned) in the heap of the signal, apart from its return addresses;
+/// a [`Despawn`] tile without a `name` uses it to delete that copy once the call is over.
+/// See [`UpdateContext::instantiate_pane`] for more information.
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+pub struct Spawn {
+    pub source: String,
+    pub name: String,
+    pub position: (i32, i32),
+    pub entry: (usize, usize),
+}
+
+impl Spawn {
+    pub fn new(source: String, name: String, position: (i32, i32), entry: (usize, usize)) -> Self {
+        Self {
+            source,
+            name,
+            position,
+            entry,
+        }
+    }
+}
+
+impl Tile for Spawn {
+    fn update<'b>(&'b mut self, mut context: UpdateContext<'b>) {
+        if let Some(mut signal) = context.take_signal() {
+            let name = context.unique_pane_name(&self.name);
+            let coordinates = (name.clone(), self.entry.0, self.entry.1);
+
+            context.instantiate_pane(self.source.clone(), name.clone(), self.position);
+            signal.push_spawned(name);
+            context.send_outbound(coordinates, signal);
+        }
+
+        if context.state() != State::Idle {
+            context.next_state();
+        }
+    }
+
+    fn draw_simple(&self, state: State) -> TextChar {
+        TextChar::from_state('@', state)
+    }
+}
+
+/// Deletes the pane named `name` whenever a signal goes through it.
+/// The signal then keeps going in the same direction.
+///
+/// If `name` is empty, then the name of the last pane [spawned](Signal::pop_spawned) for the signal is popped, and that pane is deleted instead:
+/// this deletes the copy that the signal was last sent into by a [`Spawn`] tile.
+/// If the signal has no spawned pane, then it is marked as [errored](Signal::set_error).
+///
+/// Since a pane is deleted along with its signals, the tile should be placed outside of the pane that it deletes,
+/// where the signal goes once the call is over.
+///
+/// See [`UpdateContext::delete_pane`] for more information.
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+pub struct Despawn {
+    pub name: String,
+}
+
+impl Despawn {
+    pub fn new(name: String) -> Self {
+        Self { name }
+    }
+}
+
+impl Tile for Despawn {
+    fn update<'b>(&'b mut self, mut context: UpdateContext<'b>) {
+        if let Some(mut signal) = context.take_signal() {
+            if !self.name.is_empty() {
+                context.delete_pane(self.name.clone());
+            } else if let Some(name) = signal.pop_spawned() {
+                context.delete_pane(name);
+            } else {
+                signal.set_error("Despawn: no spawned pane");
+            }
+
+            let _ = context.send_direction(signal.direction(), signal);
+        }
+
+        if context.state() != State::Idle {
+            context.next_state();
+        }
+    }
+
+    fn draw_simple(&self, state: State) -> TextChar {
+        TextChar::from_state('X', state)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_spawn_despawn() {
+        use crate::{Diode, Wire};
+        use Direction::*;
+        use Orientation::*;
+
+        fn copies(world: &World) -> Vec<String> {
+            world
+                .pane_order()
+                .iter()
+                .filter(|name| name.starts_with("copy#"))
+                .cloned()
+                .collect()
+        }
+
+        let mut main_pane = test_tile_setup!(
+            3,
+            2,
+            [
+                Diode::new(Right),
+                Spawn::new(
+                    String::from("template"),
+                    String::from("copy"),
+                    (0, 4),
+                    (0, 0)
+                ),
+                (),
+                Diode::new(Right),
+                Despawn::new(String::new()),
+                Wire::new(Horizontal),
+            ]
+        );
+        test_set_signal!(main_pane, (0, 0), Right);
+
+        let mut template = test_tile_setup!(2, 1, [Wire::new(Any), Wire::new(Horizontal)]);
+        template.set_position((0, 2));
+
+        let mut world = World::new();
+        world.set_pane(String::from("main"), main_pane);
+        world.set_pane(String::from("template"), template);
+
+        world.step();
+        assert_signal!(world.get_pane("main").unwrap(), (1, 0));
+        assert!(copies(&world).is_empty());
+
+        world.step();
+        let first = copies(&world);
+        assert_eq!(first.len(), 1);
+        let copy = world.get_pane(&first[0]).unwrap();
+        assert_eq!(copy.position(), (0, 4));
+        assert_signal!(copy, (0, 0));
+        assert_no_signal!(world.get_pane("template").unwrap(), (0, 0));
+
+        world.step();
+        assert_signal!(world.get_pane(&first[0]).unwrap(), (1, 0));
+
+        // Spawning again creates another copy; the name of the copy is recorded apart from the return addresses
+        world
+            .get_pane_mut("main")
+            .unwrap()
+            .set_signal((1, 0), Signal::empty((1, 0), Right));
+        world.step();
+        let names = copies(&world);
+        assert_eq!(names.len(), 2);
+        assert_eq!(names[0], first[0]);
+        let second = names[1].clone();
+
+        let signal = world
+            .get_pane(&second)
+            .unwrap()
+            .get((0, 0))
+            .unwrap()
+            .signal()
+            .cloned()
+            .unwrap();
+        assert_eq!(
+            signal.get_var(Signal::SPAWNED_VAR),
+            Some(&Value::from(vec![second.clone()]))
+        );
+        assert!(signal.return_addresses().is_empty());
+
+        // Despawning deletes the copy recorded in the signal
+        world
+            .get_pane_mut("main")
+            .unwrap()
+            .set_signal((0, 1), signal.clone_move(Right));
+        world.step();
+        world.step();
+        assert!(world.get_pane(&second).is_none());
+        assert_eq!(copies(&world), first);
+        assert_signal!(world.get_pane("main").unwrap(), (2, 1));
+        assert!(world
+            .get_pane("main")
+            .unwrap()
+            .get((2, 1))
+            .unwrap()
+            .signal()
+            .unwrap()
+            .heap()
+            .is_empty());
+
+        // Without a spawned pane, the signal is errored
+        world.step();
+        world.step();
+        world
+            .get_pane_mut("main")
+            .unwrap()
+            .set_signal((1, 1), Signal::empty((1, 1), Right));
+        world.step();
+        let main = world.get_pane("main").unwrap();
+        assert_eq!(
+            main.get((2, 1)).unwrap().signal().unwrap().error(),
+            Some("Despawn: no spawned pane")
+        );
+    }
+
+    #[test]
+    fn test_unique_pane_name() {
+        use Direction::*;
+
+        // Two spawners updated during the same cycle get different names, which don't depend on the order of the updates
+        let pane = test_tile_setup!(
+            2,
+            1,
+            [
+                Spawn::new(
+                    String::from("template"),
+                    String::from("copy"),
+                    (0, 4),
+                    (0, 0)
+                ),
+                Spawn::new(
+                    String::from("template"),
+                    String::from("copy"),
+                    (0, 6),
+                    (0, 0)
+                )
+            ]
+        );
+
+        let run = |positions: [(usize, usize); 2]| {
+            let mut world = World::new();
+            world.set_pane(String::from("main"), pane.clone());
+            world.set_pane(String::from("template"), Pane::empty(1, 1).unwrap());
+            for position in positions {
+                world
+                    .get_pane_mut("main")
+                    .unwrap()
+                    .set_signal(position, Signal::empty(position, Right));
+            }
+            world.step();
+
+            let mut names: Vec<(String, (i32, i32))> = world
+                .pane_order()
+                .iter()
+                .filter(|name| name.starts_with("copy#0."))
+                .map(|name| (name.clone(), world.get_pane(name).unwrap().position()))
+                .collect();
+            names.sort();
+            names
+        };
+
+        let names = run([(0, 0), (1, 0)]);
+        assert_eq!(names.len(), 2);
+        assert_ne!(names[0].0, names[1].0);
+        assert_eq!(run([(1, 0), (0, 0)]), names);
+    }
+}
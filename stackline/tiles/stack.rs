@@ -0,0 +1,207 @@
+//! Stack inspection tiles: look at the stack of signals without modifying the values in it
+//!
+//! All of these tiles, except [`TypeRouter`], let the signal keep going in the same direction.
+
+use crate::prelude::*;
+use crate::tile::prelude::*;
+
+/// Pushes the number of values in the stack of incomming signals (see [`Signal::len`]), as an [`Int`](Value::Int).
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+pub struct Depth;
+
+impl Depth {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Tile for Depth {
+    fn update<'b>(&'b mut self, mut context: UpdateContext<'b>) {
+        if let Some(mut signal) = context.take_signal() {
+            let depth = signal.len() as i64;
+            signal.push(Value::Int(depth));
+
+            let _ = context.send_direction(signal.direction(), signal);
+        }
+
+        if context.state() != State::Idle {
+            context.next_state();
+        }
+    }
+
+    fn draw_simple(&self, state: State) -> TextChar {
+        TextChar::from_state('n', state)
+    }
+}
+
+/// Pushes the [name of the type](Value::type_name) of the value on top of the stack, as a [`String`](Value::String).
+/// If the stack is empty, then `"empty"` is pushed.
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+pub struct TypeOf;
+
+impl TypeOf {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Tile for TypeOf {
+    fn update<'b>(&'b mut self, mut context: UpdateContext<'b>) {
+        if let Some(mut signal) = context.take_signal() {
+            let name = signal.stack().last().map_or("empty", Value::type_name);
+            signal.push(Value::from(name));
+
+            let _ = context.send_direction(signal.direction(), signal);
+        }
+
+        if context.state() != State::Idle {
+            context.next_state();
+        }
+    }
+
+    fn draw_simple(&self, state: State) -> TextChar {
+        TextChar::from_state('t', state)
+    }
+}
+
+/// Sends incomming signals in a direction that depends on the type of the value on top of their stack:
+/// - towards `number` for a [`Number`](Value::Number) or an [`Int`](Value::Int)
+/// - towards `string` for a [`String`](Value::String)
+/// - towards `empty` if the stack is empty
+/// - towards `other` for any other value
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TypeRouter {
+    pub number: Direction,
+    pub string: Direction,
+    pub empty: Direction,
+    pub other: Direction,
+}
+
+impl TypeRouter {
+    pub fn new(number: Direction, string: Direction, empty: Direction, other: Direction) -> Self {
+        Self {
+            number,
+            string,
+            empty,
+            other,
+        }
+    }
+}
+
+impl Default for TypeRouter {
+    fn default() -> Self {
+        Self::new(
+            Direction::Right,
+            Direction::Down,
+            Direction::Left,
+            Direction::Up,
+        )
+    }
+}
+
+impl Tile for TypeRouter {
+    fn update<'b>(&'b mut self, mut context: UpdateContext<'b>) {
+        if let Some(signal) = context.take_signal() {
+            let direction = match signal.stack().last() {
+                Some(Value::Number(_) | Value::Int(_)) => self.number,
+                Some(Value::String(_)) => self.string,
+                Some(_) => self.other,
+                None => self.empty,
+            };
+
+            let _ = context.send_direction(direction, signal.moved(direction));
+        }
+
+        if context.state() != State::Idle {
+            context.next_state();
+        }
+    }
+
+    fn draw_simple(&self, state: State) -> TextChar {
+        TextChar::from_state('T', state)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Wire;
+    use Direction::*;
+    use Orientation::*;
+
+    #[test]
+    fn test_depth_type_of() {
+        let mut pane = test_tile_setup!(
+            4,
+            1,
+            [
+                Depth::new(),
+                TypeOf::new(),
+                TypeOf::new(),
+                Wire::new(Horizontal)
+            ]
+        );
+
+        pane.set_signal((0, 0), crate::signal!((0, 0), Right, [1.5, "a"]));
+        for _ in 0..3 {
+            pane.step();
+        }
+
+        assert_eq!(
+            pane.get((3, 0)).unwrap().signal().unwrap().stack(),
+            &vec![
+                Value::Number(1.5),
+                Value::from("a"),
+                Value::Int(2),
+                Value::from("int"),
+                Value::from("string")
+            ]
+        );
+
+        pane.step();
+        pane.step();
+
+        pane.set_signal((1, 0), Signal::empty((1, 0), Right));
+        pane.step();
+        assert_eq!(
+            pane.get((2, 0)).unwrap().signal().unwrap().stack(),
+            &vec![Value::from("empty")]
+        );
+    }
+
+    #[test]
+    fn test_type_router() {
+        let cases = [
+            (vec![Value::Int(1)], (2, 1)),
+            (vec![Value::Number(1.0)], (2, 1)),
+            (vec![Value::from("a")], (1, 2)),
+            (vec![], (0, 1)),
+            (vec![Value::Int(1), Value::Bool(true)], (1, 0)),
+        ];
+
+        for (stack, position) in cases {
+            let mut pane = test_tile_setup!(
+                3,
+                3,
+                [
+                    (),
+                    Wire::new(Any),
+                    (),
+                    Wire::new(Any),
+                    TypeRouter::default(),
+                    Wire::new(Any),
+                    (),
+                    Wire::new(Any),
+                    (),
+                ]
+            );
+
+            let mut signal = Signal::empty((1, 1), Up);
+            *signal.stack_mut() = stack;
+            pane.set_signal((1, 1), signal);
+            pane.step();
+
+            assert_signal!(pane, position);
+        }
+    }
+}
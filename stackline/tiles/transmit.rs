@@ -33,11 +33,83 @@ impl Tile for Teleporter {
     }
 }
 
+/// Instantly sends any incomming signals to `coordinates`, after recording `return_to` as their return address.
+/// A [`Return`] tile will later send the signal back to `return_to`.
+///
+/// This lets several `Call` tiles share the same pane as a subroutine.
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+pub struct Call {
+    pub coordinates: (String, usize, usize),
+    pub return_to: (String, usize, usize),
+}
+
+impl Call {
+    pub fn new(coordinates: (String, usize, usize), return_to: (String, usize, usize)) -> Self {
+        Self {
+            coordinates,
+            return_to,
+        }
+    }
+}
+
+impl Tile for Call {
+    fn update<'b>(&'b mut self, mut context: UpdateContext<'b>) {
+        if let Some(mut signal) = context.take_signal() {
+            signal.push_return(self.return_to.clone());
+            context.send_outbound(self.coordinates.clone(), signal);
+        }
+
+        if context.state() != State::Idle {
+            context.next_state();
+        }
+    }
+
+    fn draw_simple(&self, state: State) -> TextChar {
+        TextChar::from_state('C', state)
+    }
+}
+
+/// Instantly sends any incomming signals back to the last return address recorded by a [`Call`] tile.
+/// Signals without a return address are marked as [errored](Signal::set_error) and keep going in the same direction.
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+pub struct Return;
+
+impl Return {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Tile for Return {
+    fn update<'b>(&'b mut self, mut context: UpdateContext<'b>) {
+        if let Some(mut signal) = context.take_signal() {
+            match signal.pop_return() {
+                Some(coordinates) => context.send_outbound(coordinates, signal),
+                None => {
+                    signal.set_error("Return: no return address");
+                    let _ = context.send_direction(signal.direction(), signal);
+                }
+            }
+        }
+
+        if context.state() != State::Idle {
+            context.next_state();
+        }
+    }
+
+    fn draw_simple(&self, state: State) -> TextChar {
+        TextChar::from_state('R', state)
+    }
+}
+
 /// Sends a signal through a virtual wire towards `coordinates`.
 #[derive(Clone, Debug, Serialize, Deserialize, Default)]
 pub struct Sender {
     pub coordinates: (String, usize, usize),
     pub path: Vec<(i32, i32)>, // x, y
+    /// Indices `i` of `path` for which the virtual wire wraps around a pane between `path[i]` and `path[i + 1]`
+    #[serde(default)]
+    pub jumps: Vec<usize>,
     pub length: usize,
     pub signals: Vec<(Signal, usize)>,
 }
@@ -47,6 +119,7 @@ impl Sender {
         Self {
             coordinates: (name, x, y),
             path: Vec::new(),
+            jumps: Vec::new(),
             length: 0,
             signals: Vec::new(),
         }
@@ -61,16 +134,16 @@ impl Sender {
         struct Pos(i32, i32);
 
         impl Pos {
+            // Neighbors may lie on the other side of a pane that wraps around
             fn neighbors(&self, world: &World) -> [(Pos, i32); 4] {
-                let x = self.0;
-                let y = self.1;
-
-                [
-                    Pos(x + 1, y).with_weight(world),
-                    Pos(x - 1, y).with_weight(world),
-                    Pos(x, y + 1).with_weight(world),
-                    Pos(x, y - 1).with_weight(world),
-                ]
+                [(1, 0), (-1, 0), (0, 1), (0, -1)].map(|offset| {
+                    let (x, y) = world.offset((self.0, self.1), offset);
+                    Pos(x, y).with_weight(world)
+                })
+            }
+
+            fn is_adjacent(&self, other: &Pos) -> bool {
+                (self.0 - other.0).abs() + (self.1 - other.1).abs() <= 1
             }
 
             fn with_weight(self, world: &World) -> (Self, i32) {
@@ -111,11 +184,28 @@ impl Sender {
                 |&node| node == target,
             ) {
                 self.path = Vec::new();
+                self.jumps = Vec::new();
                 self.path.push((best_path[0].0, best_path[0].1));
 
-                for (prev, current) in best_path.iter().zip(best_path.iter().skip(1)).skip(1) {
+                for (prev, current) in best_path.iter().zip(best_path.iter().skip(1)) {
+                    let last = self.path[self.path.len() - 1];
+
+                    // If the path wrapped around a pane, push both ends of the jump to self.path
+                    if !prev.is_adjacent(current) {
+                        if last != prev.into() {
+                            self.path.push(prev.into());
+                        }
+                        self.jumps.push(self.path.len() - 1);
+                        self.path.push(current.into());
+                        continue;
+                    }
+
+                    if last == prev.into() {
+                        continue;
+                    }
+
                     // If self.path.last(), prev, current aren't aligned, push prev to self.path
-                    let prev_x_aligned = self.path[self.path.len() - 1].0 == prev.0;
+                    let prev_x_aligned = last.0 == prev.0;
                     let curr_x_aligned = prev.0 == current.0;
                     if prev_x_aligned != curr_x_aligned {
                         self.path.push(prev.into());
@@ -165,7 +255,11 @@ impl Tile for Sender {
     // TODO: read self.signals to determine the state of each char
     // TODO: automated test
     fn draw(&self, x: i32, y: i32, _state: State, surface: &mut TextSurface) {
-        for (prev, next) in self.path.iter().zip(self.path.iter().skip(1)) {
+        for (index, (prev, next)) in self.path.iter().zip(self.path.iter().skip(1)).enumerate() {
+            if self.jumps.contains(&index) {
+                continue;
+            }
+
             if prev.0 != next.0 {
                 // Draw the diode of the corner
                 let ch = if next.0 > prev.0 { '>' } else { '<' };
@@ -330,6 +424,93 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_call_return() {
+        use crate::{Diode, Wire};
+        use Direction::*;
+        use Orientation::*;
+
+        let main_pane = test_tile_setup!(
+            4,
+            2,
+            [
+                Diode::new(Right),
+                Call::new((String::from("sub"), 0, 0), (String::from("main"), 3, 0)),
+                (),
+                Wire::new(Horizontal),
+                Diode::new(Right),
+                Call::new((String::from("sub"), 0, 0), (String::from("main"), 3, 1)),
+                (),
+                Wire::new(Horizontal),
+            ]
+        );
+
+        let mut sub_pane = test_tile_setup!(2, 1, [Wire::new(Horizontal), Return::new()]);
+        sub_pane.set_position((0, 3));
+
+        let mut world = World::new();
+        world.set_pane(String::from("main"), main_pane);
+        world.set_pane(String::from("sub"), sub_pane);
+
+        // Both callers share the same subroutine, and each get their signal back
+        for (y, other_y) in [(0, 1), (1, 0)] {
+            world
+                .get_pane_mut("main")
+                .unwrap()
+                .set_signal((0, y), Signal::empty((0, y), Right));
+
+            world.step();
+            world.step();
+            assert_signal!(world.get_pane("sub").unwrap(), (0, 0));
+            assert_eq!(
+                world
+                    .get_pane("sub")
+                    .unwrap()
+                    .get((0, 0))
+                    .unwrap()
+                    .signal()
+                    .unwrap()
+                    .return_addresses(),
+                &vec![(String::from("main"), 3, y)]
+            );
+
+            world.step();
+            world.step();
+            assert_signal!(world.get_pane("main").unwrap(), (3, y));
+            assert_no_signal!(world.get_pane("main").unwrap(), (3, other_y));
+            assert!(world
+                .get_pane("main")
+                .unwrap()
+                .get((3, y))
+                .unwrap()
+                .signal()
+                .unwrap()
+                .return_addresses()
+                .is_empty());
+
+            for _ in 0..4 {
+                world.step();
+            }
+        }
+    }
+
+    #[test]
+    fn test_return_without_address() {
+        use crate::Wire;
+        use Direction::*;
+        use Orientation::*;
+
+        let mut pane = test_tile_setup!(2, 1, [Return::new(), Wire::new(Horizontal)]);
+
+        test_set_signal!(pane, (0, 0), Right);
+        pane.step();
+        assert_signal!(pane, (1, 0));
+        assert_eq!(
+            pane.get((1, 0)).unwrap().signal().unwrap().error(),
+            Some("Return: no return address")
+        );
+    }
+
     #[test]
     fn test_sender_instantaneous() {
         use crate::Wire;
@@ -486,4 +667,38 @@ mod test {
             (2, 2)
         ]);
     }
+
+    #[test]
+    fn test_sender_pathfinding_wrap() {
+        use crate::Wire;
+
+        let mut main_pane = test_tile_setup!(5, 3, [
+            (), (), (), (), (),
+            Wire::new(Orientation::Any), (), (), (), Sender::new(String::from("main"), 0, 1),
+            (), (), (), (), (),
+        ]);
+        main_pane.set_wrap(true, false);
+
+        let mut world = World::new();
+        world.set_pane(String::from("main"), main_pane);
+
+        let mut tile = world
+            .get_pane("main")
+            .unwrap()
+            .borrow_mut_as::<Sender>((4, 1))
+            .unwrap();
+
+        tile.calculate_path((4, 1), &world);
+
+        // The path goes through the right edge of the pane and comes out of its left edge
+        assert_eq!(tile.path, [(4, 1), (0, 1)]);
+        assert_eq!(tile.jumps, [0]);
+        assert_eq!(tile.length, 1);
+
+        let mut surface = TextSurface::new(5, 3);
+        tile.draw(0, 0, State::Idle, &mut surface);
+        for x in 1..4 {
+            assert_eq!(surface.get(x, 1), Some(TextChar::default()));
+        }
+    }
 }
@@ -0,0 +1,307 @@
+//! Variable tiles: manipulate the heap of named values carried by signals and the global variables of the world
+
+use crate::prelude::*;
+use crate::tile::prelude::*;
+
+/// Pops a value from the stack of incomming signals and stores it in their heap, under the name `key`.
+/// The signal then keeps going in the same direction.
+///
+/// If the stack of the signal is empty, then the signal is marked as [errored](Signal::set_error).
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+pub struct SetVar {
+    pub key: String,
+}
+
+impl SetVar {
+    pub fn new(key: impl Into<String>) -> Self {
+        Self { key: key.into() }
+    }
+}
+
+impl Tile for SetVar {
+    fn update<'b>(&'b mut self, mut context: UpdateContext<'b>) {
+        if let Some(mut signal) = context.take_signal() {
+            match signal.pop() {
+                Some(value) => {
+                    signal.set_var(self.key.clone(), value);
+                }
+                None => signal.set_error("SetVar: the stack is empty"),
+            }
+
+            let _ = context.send_direction(signal.direction(), signal);
+        }
+
+        if context.state() != State::Idle {
+            context.next_state();
+        }
+    }
+
+    fn draw_simple(&self, state: State) -> TextChar {
+        TextChar::from_state('=', state)
+    }
+}
+
+/// Pushes a copy of the value named `key` in the heap of incomming signals onto their stack.
+/// The signal then keeps going in the same direction.
+///
+/// If there is no such value in the heap of the signal, then nothing is pushed and the signal is marked as [errored](Signal::set_error).
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+pub struct GetVar {
+    pub key: String,
+}
+
+impl GetVar {
+    pub fn new(key: impl Into<String>) -> Self {
+        Self { key: key.into() }
+    }
+}
+
+impl Tile for GetVar {
+    fn update<'b>(&'b mut self, mut context: UpdateContext<'b>) {
+        if let Some(mut signal) = context.take_signal() {
+            match signal.get_var(&self.key).cloned() {
+                Some(value) => signal.push(value),
+                None => signal.set_error(format!("GetVar: unknown variable '{}'", self.key)),
+            }
+
+            let _ = context.send_direction(signal.direction(), signal);
+        }
+
+        if context.state() != State::Idle {
+            context.next_state();
+        }
+    }
+
+    fn draw_simple(&self, state: State) -> TextChar {
+        TextChar::from_state('$', state)
+    }
+}
+
+/// Removes the value named `key` from the heap of incomming signals.
+/// The signal then keeps going in the same direction.
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+pub struct DelVar {
+    pub key: String,
+}
+
+impl DelVar {
+    pub fn new(key: impl Into<String>) -> Self {
+        Self { key: key.into() }
+    }
+}
+
+impl Tile for DelVar {
+    fn update<'b>(&'b mut self, mut context: UpdateContext<'b>) {
+        if let Some(mut signal) = context.take_signal() {
+            signal.remove_var(&self.key);
+
+            let _ = context.send_direction(signal.direction(), signal);
+        }
+
+        if context.state() != State::Idle {
+            context.next_state();
+        }
+    }
+
+    fn draw_simple(&self, state: State) -> TextChar {
+        TextChar::from_state('!', state)
+    }
+}
+
+/// Pops a value from the stack of incomming signals and stores it in the global variable `key` of the [`World`].
+/// The signal then keeps going in the same direction.
+///
+/// If the stack of the signal is empty, then the signal is marked as [errored](Signal::set_error).
+/// See [`UpdateContext::set_global`] for how conflicting writes are resolved.
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+pub struct SetGlobal {
+    pub key: String,
+}
+
+impl SetGlobal {
+    pub fn new(key: impl Into<String>) -> Self {
+        Self { key: key.into() }
+    }
+}
+
+impl Tile for SetGlobal {
+    fn update<'b>(&'b mut self, mut context: UpdateContext<'b>) {
+        if let Some(mut signal) = context.take_signal() {
+            match signal.pop() {
+                Some(value) => context.set_global(self.key.clone(), value),
+                None => signal.set_error("SetGlobal: the stack is empty"),
+            }
+
+            let _ = context.send_direction(signal.direction(), signal);
+        }
+
+        if context.state() != State::Idle {
+            context.next_state();
+        }
+    }
+
+    fn draw_simple(&self, state: State) -> TextChar {
+        TextChar::from_state(':', state)
+    }
+}
+
+/// Pushes a copy of the global variable `key` of the [`World`] onto the stack of incomming signals.
+/// The signal then keeps going in the same direction.
+///
+/// If there is no such variable, then nothing is pushed and the signal is marked as [errored](Signal::set_error).
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+pub struct GetGlobal {
+    pub key: String,
+}
+
+impl GetGlobal {
+    pub fn new(key: impl Into<String>) -> Self {
+        Self { key: key.into() }
+    }
+}
+
+impl Tile for GetGlobal {
+    fn update<'b>(&'b mut self, mut context: UpdateContext<'b>) {
+        if let Some(mut signal) = context.take_signal() {
+            match context.get_global(&self.key) {
+                Some(value) => signal.push(value.clone()),
+                None => signal.set_error(format!("GetGlobal: unknown global '{}'", self.key)),
+            }
+
+            let _ = context.send_direction(signal.direction(), signal);
+        }
+
+        if context.state() != State::Idle {
+            context.next_state();
+        }
+    }
+
+    fn draw_simple(&self, state: State) -> TextChar {
+        TextChar::from_state('&', state)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_variables() {
+        use crate::{Diode, Wire};
+        use Direction::*;
+
+        let mut pane = test_tile_setup!(
+            7,
+            1,
+            [
+                Diode::new(Right),
+                SetVar::new("x"),
+                GetVar::new("x"),
+                GetVar::new("x"),
+                DelVar::new("x"),
+                GetVar::new("x"),
+                Wire::new(Orientation::Horizontal),
+            ]
+        );
+
+        pane.set_signal((0, 0), crate::signal!((0, 0), Right, [1, 2]));
+
+        pane.step();
+        pane.step();
+        let signal = pane.get((2, 0)).unwrap().signal().cloned().unwrap();
+        assert_eq!(signal.stack(), &vec![Value::Int(1)]);
+        assert_eq!(signal.get_var("x"), Some(&Value::Int(2)));
+
+        pane.step();
+        pane.step();
+        let signal = pane.get((4, 0)).unwrap().signal().cloned().unwrap();
+        assert_eq!(signal.len(), 3);
+        assert_eq!(signal.stack()[2], Value::Int(2));
+
+        pane.step();
+        let signal = pane.get((5, 0)).unwrap().signal().cloned().unwrap();
+        assert_eq!(signal.get_var("x"), None);
+        assert!(signal.heap().is_empty());
+
+        // Getting a missing value leaves the stack untouched and marks the signal as errored
+        pane.step();
+        let signal = pane.get((6, 0)).unwrap().signal().cloned().unwrap();
+        assert_eq!(signal.len(), 3);
+        assert_eq!(signal.error(), Some("GetVar: unknown variable 'x'"));
+    }
+
+    #[test]
+    fn test_globals() {
+        use crate::Wire;
+        use Direction::*;
+        use Orientation::*;
+
+        let writer = test_tile_setup!(
+            2,
+            2,
+            [
+                SetGlobal::new("x"),
+                SetGlobal::new("x"),
+                SetGlobal::new("x"),
+                Wire::new(Horizontal),
+            ]
+        );
+        let mut reader = test_tile_setup!(2, 1, [GetGlobal::new("x"), Wire::new(Horizontal)]);
+        reader.set_position((0, 2));
+
+        let mut world = World::new();
+        world.set_pane(String::from("writer"), writer);
+        world.set_pane(String::from("reader"), reader);
+
+        // Signals are set in the reverse reading order, to check that writes don't depend on the update order
+        for (position, value) in [((0, 1), 3), ((1, 0), 2), ((0, 0), 1)] {
+            world
+                .get_pane_mut("writer")
+                .unwrap()
+                .set_signal(position, crate::signal!(position, Up, [value]));
+        }
+        world
+            .get_pane_mut("reader")
+            .unwrap()
+            .set_signal((0, 0), Signal::empty((0, 0), Right));
+
+        world.step();
+        // The last write in reading order wins
+        assert_eq!(world.get_global("x"), Some(&Value::Int(3)));
+        // Reads happen before the writes of the same update cycle, so the global was still missing
+        let signal = world
+            .get_pane("reader")
+            .unwrap()
+            .get((1, 0))
+            .unwrap()
+            .signal()
+            .cloned()
+            .unwrap();
+        assert!(signal.is_stack_empty());
+        assert_eq!(signal.error(), Some("GetGlobal: unknown global 'x'"));
+
+        world.step();
+        world.step();
+        world
+            .get_pane_mut("reader")
+            .unwrap()
+            .set_signal((0, 0), Signal::empty((0, 0), Right));
+        world.step();
+        assert_eq!(
+            world
+                .get_pane("reader")
+                .unwrap()
+                .get((1, 0))
+                .unwrap()
+                .signal()
+                .unwrap()
+                .stack(),
+            &vec![Value::Int(3)]
+        );
+
+        // Globals are saved along with the world
+        let serialized = serde_json::to_string(&world).unwrap();
+        let world: World = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(world.get_global("x"), Some(&Value::Int(3)));
+    }
+}
@@ -21,6 +21,8 @@ impl Tile for Wire {
 
                 if let Some(pos) = context.accepts_direction(direction) {
                     context.force_send(pos, signal.clone_move(direction)).unwrap_or_else(|_| unreachable!());
+                } else if context.crosses_edge(direction) {
+                    let _ = context.send_edge(direction, signal.clone_move(direction));
                 }
             }
         }
@@ -62,9 +64,7 @@ impl Tile for Diode {
                 return;
             }
 
-            if let Some(pos) = context.offset(self.0.into_offset()) {
-                let _ = context.send(pos, self.0, signal);
-            }
+            let _ = context.send_direction(self.0, signal);
         }
 
         if context.state() != State::Idle {
@@ -106,9 +106,7 @@ impl Resistor {
 impl Tile for Resistor {
     fn update<'b>(&'b mut self, mut context: UpdateContext<'b>) {
         if let Some(signal) = std::mem::take(&mut self.signal) {
-            if let Some(pos) = context.offset(self.direction.into_offset()) {
-                let _ = context.send(pos, self.direction, signal);
-            }
+            let _ = context.send_direction(self.direction, signal);
         }
 
         if let Some(signal) = context.take_signal() {
@@ -131,6 +129,70 @@ impl Tile for Resistor {
     }
 }
 
+/// Sends every incomming signal towards `direction`, `length` update cycles after a [`Diode`] would have.
+/// A `Delay` of length 1 thus behaves like a [`Resistor`].
+///
+/// Unlike [`Resistor`], the delay keeps accepting signals while it holds other signals,
+/// so that every signal comes out exactly `length` update cycles later.
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+pub struct Delay {
+    pub direction: Direction,
+    pub length: usize,
+    /// The signals in flight, along with the number of update cycles before they are sent
+    pub signals: Vec<(Signal, usize)>,
+}
+
+impl Delay {
+    pub fn new(direction: Direction, length: usize) -> Self {
+        Self {
+            direction,
+            length,
+            signals: Vec::new(),
+        }
+    }
+}
+
+impl Tile for Delay {
+    fn update<'b>(&'b mut self, mut context: UpdateContext<'b>) {
+        for (signal, remaining) in std::mem::take(&mut self.signals) {
+            if remaining <= 1 {
+                let _ = context.send_direction(self.direction, signal.moved(self.direction));
+            } else {
+                self.signals.push((signal, remaining - 1));
+            }
+        }
+
+        if let Some(signal) = context.take_signal() {
+            if self.length == 0 {
+                let _ = context.send_direction(self.direction, signal.moved(self.direction));
+            } else {
+                self.signals.push((signal, self.length));
+            }
+        }
+
+        if !self.signals.is_empty() {
+            context.set_state(State::Active);
+        } else if context.state() != State::Idle {
+            context.next_state();
+        }
+    }
+
+    fn accepts_while_active(&self) -> bool {
+        true
+    }
+
+    fn draw_simple(&self, state: State) -> TextChar {
+        let ch = match self.direction {
+            Direction::Up => '\u{21d1}',    // Upwards Double Arrow
+            Direction::Down => '\u{21d3}',  // Downwards Double Arrow
+            Direction::Left => '\u{21d0}',  // Leftwards Double Arrow
+            Direction::Right => '\u{21d2}', // Rightwards Double Arrow
+        };
+
+        TextChar::from_state(ch, state)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -290,4 +352,62 @@ mod test {
         assert_no_signal!(pane, (2, 0));
         assert_no_signal!(pane, (3, 0));
     }
+
+    #[test]
+    fn test_delay_transmit() {
+        use crate::Direction::*;
+
+        // Signals leave the delay after the same number of update cycles as with a resistor
+        let mut pane = test_tile_setup!(
+            3,
+            2,
+            [
+                Diode::new(Right),
+                Resistor::new(Right),
+                Diode::new(Right),
+                Diode::new(Right),
+                Delay::new(Right, 1),
+                Diode::new(Right),
+            ]
+        );
+
+        test_set_signal!(pane, (0, 0), Right);
+        test_set_signal!(pane, (0, 1), Right);
+
+        for _ in 0..3 {
+            pane.step();
+            assert_eq!(
+                pane.get((2, 0)).unwrap().signal().is_some(),
+                pane.get((2, 1)).unwrap().signal().is_some()
+            );
+        }
+        assert_signal!(pane, (2, 1));
+    }
+
+    #[test]
+    fn test_delay_pipeline() {
+        use crate::Direction::*;
+
+        // A delay of length 0 sends signals right away and accepts a new signal on every update cycle
+        let mut pane = test_tile_setup!(2, 1, [Delay::new(Right, 4), Delay::new(Right, 0)]);
+
+        // Send a signal on each of the first three update cycles
+        let mut arrivals = Vec::new();
+        for step in 0..12 {
+            if step < 3 {
+                pane.set_signal((0, 0), crate::signal!((0, 0), Right, [step]));
+            }
+            pane.step();
+
+            if let Some(signal) = pane.get((1, 0)).unwrap().signal() {
+                arrivals.push((step, signal.stack()[0].clone()));
+            }
+        }
+
+        assert_eq!(
+            arrivals,
+            vec![(4, Value::Int(0)), (5, Value::Int(1)), (6, Value::Int(2))]
+        );
+        assert_eq!(pane.get((0, 0)).unwrap().state(), State::Idle);
+    }
 }